@@ -1,10 +1,19 @@
+use std::process::ExitCode;
+
 use clap::Parser;
 use ebar::TryRun;
 
-fn main() {
-    let app = ebar::tools::json::Json::parse();
-    let ctx = ebar::Context {};
-    if let Err(e) = app.run(&ctx) {
-        eprintln!("{}", &e);
-    }
+#[derive(Debug, Parser)]
+struct App {
+    #[clap(flatten)]
+    global: ebar::GlobalArgs,
+
+    #[clap(flatten)]
+    json: ebar::tools::json::Json,
+}
+
+fn main() -> ExitCode {
+    let app = App::parse();
+    let ctx = app.global.context();
+    app.json.exit(&ctx).into()
 }
@@ -0,0 +1,63 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde_json::{json, Value};
+use view::{CompiledPath, PathCache, ViewPath};
+
+const PATHS: [&str; 3] = [
+    "services[42].owner.name",
+    r#"services[-1].(host | address)"#,
+    "meta.version",
+];
+
+fn document() -> Value {
+    let services = (0..100)
+        .map(|i| {
+            json!({
+                "id": i,
+                "address": format!("10.0.0.{}", i),
+                "owner": {"name": format!("team-{}", i % 7)},
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({"services": services, "meta": {"version": "1.2.3"}})
+}
+
+fn bench_search_path(c: &mut Criterion) {
+    let value = document();
+    let mut group = c.benchmark_group("search_path");
+
+    group.bench_function("parse_str", |b| {
+        b.iter(|| {
+            for path in PATHS {
+                let path = ViewPath::parse_str(black_box(path)).unwrap();
+                black_box(ejson::search_path(&value, path).unwrap());
+            }
+        })
+    });
+
+    let compiled = PATHS
+        .iter()
+        .map(|path| CompiledPath::compile(path).unwrap())
+        .collect::<Vec<_>>();
+    group.bench_function("compiled", |b| {
+        b.iter(|| {
+            for path in &compiled {
+                black_box(ejson::search_path(&value, black_box(path)).unwrap());
+            }
+        })
+    });
+
+    let mut cache = PathCache::new(16);
+    group.bench_function("cache", |b| {
+        b.iter(|| {
+            for path in PATHS {
+                let path = cache.get(black_box(path)).unwrap();
+                black_box(ejson::search_path(&value, &*path).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_search_path);
+criterion_main!(benches);
@@ -0,0 +1,133 @@
+//! Evaluation of the `[?(...)]` filter segments.
+
+use std::cmp::Ordering;
+
+use view::{CompareOp, Filter, Leaf, Literal, Navigable, Operand, Pattern};
+
+use crate::search_all;
+
+/// Tests the filter against the value, which is the `@` of the filter.
+///
+/// A path operand may match several values, the comparison holds if it
+/// holds for any of them, except `!=` which holds if `==` holds for none.
+pub(crate) fn test<N: Navigable>(filter: &Filter, value: &N) -> bool {
+    match filter {
+        Filter::Exists(path) => !search_all(value, path.as_view_path()).is_empty(),
+        Filter::Compare(left, CompareOp::Ne, right) => !test(
+            &Filter::compare(left.clone(), CompareOp::Eq, right.clone()),
+            value,
+        ),
+        Filter::Compare(left, op, right) => {
+            let right = operand(right, value);
+            operand(left, value)
+                .iter()
+                .any(|l| right.iter().any(|r| compare(l, *op, r)))
+        }
+        Filter::And(left, right) => test(left, value) && test(right, value),
+        Filter::Or(left, right) => test(left, value) || test(right, value),
+        Filter::Not(filter) => !test(filter, value),
+    }
+}
+
+/// Item is a value an operand evaluates to.
+enum Item<'a, N> {
+    Node(&'a N),
+    Literal(Leaf<'a>),
+    Pattern(&'a Pattern),
+}
+
+impl<'a, N: Navigable> Item<'a, N> {
+    fn leaf(&self) -> Option<Leaf<'_>> {
+        match self {
+            Item::Node(node) => node.as_leaf(),
+            Item::Literal(leaf) => Some(leaf.clone()),
+            Item::Pattern(pattern) => Some(Leaf::String(pattern.as_str().into())),
+        }
+    }
+}
+
+fn operand<'a, N: Navigable>(operand: &'a Operand, value: &'a N) -> Vec<Item<'a, N>> {
+    match operand {
+        Operand::Path(path) => search_all(value, path.as_view_path())
+            .into_iter()
+            .map(|(_, v)| Item::Node(v))
+            .collect(),
+        Operand::Literal(literal) => vec![Item::Literal(literal_leaf(literal))],
+        Operand::Pattern(pattern) => vec![Item::Pattern(pattern)],
+    }
+}
+
+fn literal_leaf(literal: &Literal) -> Leaf<'_> {
+    match literal {
+        Literal::Null => Leaf::Null,
+        Literal::Bool(b) => Leaf::Bool(*b),
+        Literal::Number(n) => Leaf::Number(n.into()),
+        Literal::String(s) => Leaf::String(s.into()),
+    }
+}
+
+fn compare<N: Navigable>(left: &Item<'_, N>, op: CompareOp, right: &Item<'_, N>) -> bool {
+    let (l, r) = (left.leaf(), right.leaf());
+    match op {
+        CompareOp::Eq => match (left, right) {
+            (Item::Node(l), Item::Node(r)) if l.len().is_some() => equal(*l, *r),
+            _ => match (l, r) {
+                (Some(l), Some(r)) => ordering(&l, &r) == Some(Ordering::Equal) || l == r,
+                _ => false,
+            },
+        },
+        CompareOp::Ne => !compare(left, CompareOp::Eq, right),
+        CompareOp::Lt => ordering_of(l, r) == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering_of(l, r), Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering_of(l, r) == Some(Ordering::Greater),
+        CompareOp::Ge => matches!(ordering_of(l, r), Some(Ordering::Greater | Ordering::Equal)),
+        // A regex read from the document is compiled for each comparison,
+        // an invalid one matches nothing.
+        CompareOp::Match => match (l, right, r) {
+            (Some(Leaf::Null) | None, _, _) => false,
+            (Some(l), Item::Pattern(pattern), _) => pattern.is_match(&l.to_string()),
+            (Some(l), _, Some(Leaf::String(pattern))) => regex::Regex::new(&pattern)
+                .map(|r| r.is_match(&l.to_string()))
+                .unwrap_or(false),
+            _ => false,
+        },
+    }
+}
+
+fn ordering_of(left: Option<Leaf<'_>>, right: Option<Leaf<'_>>) -> Option<Ordering> {
+    ordering(&left?, &right?)
+}
+
+/// Orders numbers by value and strings lexicographically, other values
+/// are not ordered.
+fn ordering(left: &Leaf<'_>, right: &Leaf<'_>) -> Option<Ordering> {
+    match (left, right) {
+        (Leaf::Number(_), Leaf::Number(_)) => left.as_f64()?.partial_cmp(&right.as_f64()?),
+        (Leaf::String(l), Leaf::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+/// Compares two nodes deeply, the keys of maps in any order.
+fn equal<N: Navigable>(left: &N, right: &N) -> bool {
+    match (left.as_leaf(), right.as_leaf()) {
+        (Some(l), Some(r)) => ordering(&l, &r) == Some(Ordering::Equal) || l == r,
+        (None, None) if left.is_array() && right.is_array() => {
+            left.len() == right.len()
+                && left
+                    .children()
+                    .zip(right.children())
+                    .all(|((_, l), (_, r))| equal(l, r))
+        }
+        (None, None) if left.is_map() && right.is_map() => {
+            left.len() == right.len()
+                && left.children().all(|(k, l)| match k {
+                    view::SegmentBuf::Field(k) => {
+                        right.get_field(k.as_str()).is_some_and(|r| equal(l, r))
+                    }
+                    _ => false,
+                })
+        }
+        _ => false,
+    }
+}
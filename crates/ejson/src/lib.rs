@@ -1,140 +1,766 @@
 //! This module provides some useful help functions for JSON.
 //!
+//! The lookups are written against `view::Navigable`, so they also work
+//! on any other document implementing it.
 
-use serde_json::Value;
-use view::{Segment, SegmentBuf, ViewPath, ViewPathBuf};
-
-pub fn nest_find_value<'a>(value: &'a Value, expect: &str) -> Option<Vec<ViewPathBuf>> {
-    match nest_find_by(value, |v| match v {
-        Value::Null => false,
-        Value::Bool(b) => b.to_string().eq(expect),
-        Value::Number(n) => n.to_string().eq(expect),
-        Value::String(s) => s.eq(expect),
-        Value::Array(_) => false,
-        Value::Object(_) => false,
-    }) {
+mod filter;
+mod mutate;
+mod span;
+
+pub use mutate::{insert_path, remove_path, search_path_mut, set_path};
+pub use span::{Location, SpanMap};
+
+use std::borrow::Borrow;
+
+use view::{FieldBuf, Navigable, Segment, SegmentBuf, SegmentRef, ViewPathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("expected {expected} at `{path}`, found {found}")]
+    TypeMismatch {
+        path: ViewPathBuf,
+        expected: &'static str,
+        found: &'static str,
+    },
+    #[error("`{segment}` is not found at `{path}`")]
+    MissingKey {
+        path: ViewPathBuf,
+        segment: SegmentBuf,
+    },
+    #[error("index {index} is out of range at `{path}`, the array has {len} elements")]
+    IndexOutOfRange {
+        path: ViewPathBuf,
+        index: isize,
+        len: usize,
+    },
+    #[error("`{segment}` may select several values at `{path}`")]
+    NotSingular {
+        path: ViewPathBuf,
+        segment: SegmentBuf,
+    },
+    #[error("invalid json at {location}")]
+    InvalidJson { location: Location },
+}
+
+pub fn nest_find_value<N: Navigable>(value: &N, expect: &str) -> Option<Vec<ViewPathBuf>> {
+    match nest_find_by(value, |v| v.leaf_to_string().is_some_and(|s| s == expect)) {
         Some(values) => Some(values.into_iter().map(|s| s.0).collect()),
         None => None,
     }
 }
 
-pub fn nest_find_regex<'a>(
-    value: &'a Value,
+pub fn nest_find_regex<N: Navigable>(
+    value: &N,
     expect: regex::Regex,
-) -> Option<Vec<(ViewPathBuf, &'a Value)>> {
-    nest_find_by(value, |v| match v {
-        Value::Null => false,
-        Value::Bool(b) => expect.is_match(&b.to_string()),
-        Value::Number(n) => expect.is_match(&n.to_string()),
-        Value::String(s) => expect.is_match(s),
-        Value::Array(_) => false,
-        Value::Object(_) => false,
+) -> Option<Vec<(ViewPathBuf, &N)>> {
+    nest_find_by(value, |v| {
+        v.leaf_to_string().is_some_and(|s| expect.is_match(&s))
     })
 }
 
-pub fn nest_find_by<'a>(
-    value: &'a Value,
-    predicate: impl Fn(&Value) -> bool + Clone,
-) -> Option<Vec<(ViewPathBuf, &Value)>> {
-    match value {
-        Value::Null => None,
-        Value::Bool(_) | Value::Number(_) | Value::String(_) => {
-            if predicate(value) {
-                Some(vec![(ViewPathBuf::root(), value)])
-            } else {
-                None
+pub fn nest_find_by<N: Navigable>(
+    value: &N,
+    predicate: impl Fn(&N) -> bool + Clone,
+) -> Option<Vec<(ViewPathBuf, &N)>> {
+    if value.len().is_none() {
+        return if predicate(value) {
+            Some(vec![(ViewPathBuf::root(), value)])
+        } else {
+            None
+        };
+    }
+    let mut ret = Vec::new();
+    for (seg, v) in value.children() {
+        if let Some(paths) = nest_find_by(v, predicate.clone()) {
+            for (mut path, vp) in paths {
+                path.push_front(seg.clone());
+                ret.push((path, vp));
             }
         }
+    }
+    if !ret.is_empty() {
+        Some(ret)
+    } else {
+        None
+    }
+}
 
-        Value::Array(a) => {
-            let mut ret = Vec::new();
-            for (i, v) in a.iter().enumerate() {
-                match nest_find_by(v, predicate.clone()) {
-                    Some(paths) => {
-                        for (mut path, vp) in paths {
-                            path.push_front(SegmentBuf::Index(i as isize));
-                            ret.push((path, vp));
-                        }
-                    }
-                    None => {}
-                }
-            }
-            if ret.len() > 0 {
-                Some(ret)
-            } else {
-                None
+/// Searchs the single value addressed by the path.
+///
+/// The path is either a `ViewPath` or a `&CompiledPath`, which is cheaper
+/// to evaluate many times. If the path cannot be resolved, the error tells
+/// where the resolution stopped and why.
+///
+/// Only the depth is kept while walking, the path to the failure is found
+/// again from the root when the error needs it.
+pub fn search_path<'a, 'p, N, P, S>(root: &'a N, path: P) -> Result<&'a N, Error>
+where
+    N: Navigable,
+    P: IntoIterator<Item = S>,
+    S: SegmentRef<'p>,
+{
+    let mut value = root;
+    for (depth, seg) in path.into_iter().enumerate() {
+        match child(value, seg.borrow(), seg.field_hashes()) {
+            Some(v) => value = v,
+            None => {
+                let prefix = path_to(root, value, depth).unwrap_or_else(ViewPathBuf::root);
+                return Err(failure(value, seg.borrow(), prefix));
             }
         }
-        Value::Object(m) => {
-            let mut ret = Vec::new();
-            for (k, v) in m {
-                match nest_find_by(v, predicate.clone()) {
-                    Some(paths) => {
-                        for (mut path, vp) in paths {
-                            path.push_front(SegmentBuf::Field(k.as_str().into()));
-                            ret.push((path, vp));
-                        }
-                    }
-                    None => {}
-                }
+    }
+    Ok(value)
+}
+
+/// Returns the concrete path of the node, which is `depth` levels below the
+/// root.
+fn path_to<N: Navigable>(root: &N, node: &N, depth: usize) -> Option<ViewPathBuf> {
+    if depth == 0 {
+        return std::ptr::eq(root, node).then(ViewPathBuf::root);
+    }
+    root.children().find_map(|(seg, child)| {
+        let mut path = path_to(child, node, depth - 1)?;
+        path.push_front(seg);
+        Some(path)
+    })
+}
+
+/// Searchs every value matched by the path, along with its concrete path.
+///
+/// Unlike `search_path`, wildcard segments fan out over all the elements
+/// of an array or all the values of an object, slice segments select a
+/// range of elements, filter segments select the elements or values
+/// matching a predicate and descendant segments apply the rest of the path
+/// at any depth.
+pub fn search_all<'a, 'p, N, P, S>(value: &'a N, path: P) -> Vec<(ViewPathBuf, &'a N)>
+where
+    N: Navigable,
+    P: IntoIterator<Item = S>,
+    S: Borrow<Segment<'p>>,
+{
+    let mut matches = vec![(ViewPathBuf::root(), value)];
+    for seg in path.into_iter() {
+        let seg = seg.borrow();
+        let mut next = Vec::new();
+        for (path, value) in matches {
+            if seg.is_descendant() {
+                descendants(path, value, &mut next);
+                continue;
             }
-            if ret.len() > 0 {
-                Some(ret)
-            } else {
-                None
+            for (child, v) in select(value, seg) {
+                let mut path = path.clone();
+                path.push_back(child);
+                next.push((path, v));
             }
         }
+        matches = next;
     }
+    matches
 }
 
-pub fn search_path<'a>(value: &'a Value, path: ViewPath<'a>) -> Option<&'a Value> {
-    let mut value = value;
-    for seg in path.into_iter() {
-        match search_segment(value, seg) {
-            Some(v) => value = v,
-            None => return None,
-        }
+/// Collects the value and all of its descendants in document order.
+fn descendants<'a, N: Navigable>(
+    path: ViewPathBuf,
+    value: &'a N,
+    out: &mut Vec<(ViewPathBuf, &'a N)>,
+) {
+    out.push((path.clone(), value));
+    for (child, v) in value.children() {
+        let mut path = path.clone();
+        path.push_back(child);
+        descendants(path, v, out);
     }
-    Some(value)
 }
 
-pub fn search_segment<'a>(value: &'a Value, seg: Segment<'a>) -> Option<&'a Value> {
+/// Selects the children matched by the segment, along with the concrete
+/// segment leading to each of them.
+fn select<'a, N: Navigable>(value: &'a N, seg: &Segment<'_>) -> Vec<(SegmentBuf, &'a N)> {
     match seg {
-        Segment::Field(f) => match value {
-            Value::Object(m) => m.get(f.as_str()),
-            _ => None,
+        Segment::Field(f) => value
+            .get_field(f.as_str())
+            .map(|v| (SegmentBuf::field(FieldBuf::new(f.as_str())), v))
+            .into_iter()
+            .collect(),
+        Segment::Index(i) => match value.len() {
+            Some(len) if value.is_array() => resolve_index(*i, len)
+                .and_then(|i| Some((SegmentBuf::index(i as isize), value.get_index(i)?)))
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
         },
-        Segment::Index(i) => match value {
-            Value::Array(a) => {
-                let i = i as usize;
-                if i < a.len() {
-                    Some(&a[i])
-                } else {
-                    None
-                }
-            }
-            _ => None,
+        Segment::Coalesce(c) => c
+            .iter()
+            .find_map(|f| {
+                Some((
+                    SegmentBuf::field(FieldBuf::new(f.as_str())),
+                    value.get_field(f.as_str())?,
+                ))
+            })
+            .into_iter()
+            .collect(),
+        Segment::Wildcard => value.children().collect(),
+        Segment::Slice(slice) => match value.len() {
+            Some(len) if value.is_array() => slice
+                .indices(len)
+                .into_iter()
+                .filter_map(|i| Some((SegmentBuf::index(i as isize), value.get_index(i)?)))
+                .collect(),
+            _ => Vec::new(),
         },
-        Segment::Coalesce(c) => match value {
-            Value::Object(m) => {
-                for f in c {
-                    let v = m.get(f.as_str());
-                    if v.is_some() {
-                        return v;
-                    }
-                }
-                None
+        Segment::Filter(f) => value
+            .children()
+            .filter(|(_, v)| filter::test(f, *v))
+            .collect(),
+        // Expanded by `search_all` itself.
+        Segment::Descendant => Vec::new(),
+    }
+}
+
+/// Resolves a path read by `ViewPathBuf::from_json_pointer` against the
+/// value and returns the concrete path it addresses.
+///
+/// A pointer does not tell arrays from objects, so its digit tokens are
+/// read as indexes. As RFC 6901 says, such a token addresses the field of
+/// the same name in an object, the returned path has a field there. The
+/// segments past the first missing value are kept as read, so the path
+/// still tells `search_path` what is missing and `set_path` what to create.
+pub fn resolve_pointer<N: Navigable>(root: &N, path: &ViewPathBuf) -> ViewPathBuf {
+    let mut concrete = ViewPathBuf::root();
+    let mut value = Some(root);
+    for seg in path.iter() {
+        let seg = match (seg, value) {
+            (SegmentBuf::Index(i), Some(v)) if v.is_map() => {
+                SegmentBuf::field(FieldBuf::new(i.to_string()))
             }
-            _ => None,
+            (seg, _) => seg.clone(),
+        };
+        value = value.and_then(|v| child(v, &seg.as_segment(), &[]));
+        concrete.push_back(seg);
+    }
+    concrete
+}
+
+/// Searchs the single value addressed by the segment.
+///
+/// Wildcard, descendant, slice and filter segments may match several
+/// values, so they fail with `Error::NotSingular`, use `search_all`
+/// instead.
+pub fn search_segment<'a, N: Navigable>(value: &'a N, seg: Segment<'_>) -> Result<&'a N, Error> {
+    child(value, &seg, &[]).ok_or_else(|| failure(value, &seg, ViewPathBuf::root()))
+}
+
+/// Looks the singular segment up in the value, without allocating, by the
+/// precomputed hashes of its fields if there are some.
+fn child<'a, N: Navigable>(value: &'a N, seg: &Segment<'_>, hashes: &[u64]) -> Option<&'a N> {
+    let get_field = |i: usize, name: &str| match hashes.get(i) {
+        Some(hash) => value.get_field_hashed(name, *hash),
+        None => value.get_field(name),
+    };
+    match seg {
+        Segment::Field(f) if value.is_map() => get_field(0, f.as_str()),
+        Segment::Coalesce(c) if value.is_map() => c
+            .iter()
+            .enumerate()
+            .find_map(|(i, f)| get_field(i, f.as_str())),
+        Segment::Index(i) if value.is_array() => value.get_index(resolve_index(*i, value.len()?)?),
+        _ => None,
+    }
+}
+
+/// Tells why `child` found nothing for the segment in the value found at
+/// `prefix`.
+fn failure<N: Navigable>(value: &N, seg: &Segment<'_>, prefix: ViewPathBuf) -> Error {
+    match seg {
+        Segment::Field(_) | Segment::Coalesce(_) if value.is_map() => missing(prefix, seg),
+        Segment::Index(i) if value.is_array() => out_of_range(prefix, *i, value.len().unwrap_or(0)),
+        seg => mismatch(prefix, seg, value),
+    }
+}
+
+pub(crate) fn missing(path: ViewPathBuf, seg: &Segment<'_>) -> Error {
+    Error::MissingKey {
+        path,
+        segment: seg.to_segment_buf(),
+    }
+}
+
+pub(crate) fn out_of_range(path: ViewPathBuf, index: isize, len: usize) -> Error {
+    Error::IndexOutOfRange { path, index, len }
+}
+
+pub(crate) fn mismatch<N: Navigable>(path: ViewPathBuf, seg: &Segment<'_>, value: &N) -> Error {
+    match seg {
+        Segment::Field(_) | Segment::Coalesce(_) => Error::TypeMismatch {
+            path,
+            expected: N::map_type_name(),
+            found: value.type_name(),
+        },
+        Segment::Index(_) => Error::TypeMismatch {
+            path,
+            expected: N::array_type_name(),
+            found: value.type_name(),
         },
+        seg => Error::NotSingular {
+            path,
+            segment: seg.to_segment_buf(),
+        },
+    }
+}
+
+/// Resolves `index` against an array of `len` elements.
+///
+/// Negative indexes count from the end of the array, so `-1` is the last
+/// element. Returns `None` if the index is out of range.
+pub fn resolve_index(index: isize, len: usize) -> Option<usize> {
+    let i = if index < 0 {
+        len.checked_sub(index.unsigned_abs())?
+    } else {
+        index as usize
+    };
+    if i < len {
+        Some(i)
+    } else {
+        None
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+    use view::{
+        CompiledPath, Leaf, Navigable, PathCache, Segment, SegmentBuf, SegmentRef, ViewPath,
+        ViewPathBuf,
+    };
+
+    use crate::{nest_find_value, resolve_index, resolve_pointer, search_all, search_path, Error};
+
     #[test]
     fn it_works() {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn test_resolve_index() {
+        assert_eq!(Some(0), resolve_index(0, 3));
+        assert_eq!(Some(2), resolve_index(2, 3));
+        assert_eq!(None, resolve_index(3, 3));
+        assert_eq!(Some(2), resolve_index(-1, 3));
+        assert_eq!(Some(0), resolve_index(-3, 3));
+        assert_eq!(None, resolve_index(-4, 3));
+        assert_eq!(None, resolve_index(-1, 0));
+        assert_eq!(None, resolve_index(isize::MIN, 3));
+    }
+
+    #[test]
+    fn test_search_negative_index() {
+        let value = json!({"items": [1, 2, {"a": "b"}]});
+        let search = |p| search_path(&value, ViewPath::parse_str(p).unwrap()).ok();
+        assert_eq!(Some(&json!({"a": "b"})), search("items[-1]"));
+        assert_eq!(Some(&json!("b")), search("items[-1].a"));
+        assert_eq!(Some(&json!(1)), search("items[-3]"));
+        assert_eq!(None, search("items[-4]"));
+        assert_eq!(None, search("items[-100]"));
+    }
+
+    #[test]
+    fn test_search_all_wildcard() {
+        let value = json!({
+            "services": [{"id": 1, "name": "a"}, {"id": 2}, {"id": 3, "name": "c"}],
+            "envs": {"dev": {"name": "d"}, "prod": {"name": "p"}},
+        });
+        let search = |p| {
+            search_all(&value, ViewPath::parse_str(p).unwrap())
+                .into_iter()
+                .map(|(path, v)| (path.to_string(), v.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                ("services[0].name".to_string(), json!("a")),
+                ("services[2].name".to_string(), json!("c")),
+            ],
+            search("services[*].name")
+        );
+        assert_eq!(
+            vec![
+                ("envs.dev.name".to_string(), json!("d")),
+                ("envs.prod.name".to_string(), json!("p")),
+            ],
+            search("envs.*.name")
+        );
+        assert_eq!(
+            vec![("services[2].id".to_string(), json!(3))],
+            search("services[-1].id")
+        );
+        assert!(search("services.*.id.*").is_empty());
+        assert!(matches!(
+            search_path(&value, ViewPath::parse_str("envs.*").unwrap()),
+            Err(Error::NotSingular { .. })
+        ));
+    }
+
+    #[test]
+    fn test_search_all_descendant() {
+        let value = json!({
+            "id": 0,
+            "a": {"id": 1, "b": [{"id": 2}, {"c": {"id": 3}}]},
+            "d": "id",
+        });
+        let paths = search_all(&value, ViewPath::parse_str("..id").unwrap());
+        assert_eq!(
+            vec!["id", "a.id", "a.b[0].id", "a.b[1].c.id"],
+            paths.iter().map(|(p, _)| p.to_string()).collect::<Vec<_>>()
+        );
+        for (path, v) in &paths {
+            let path = ViewPathBuf::parse_str(&path.to_string()).unwrap();
+            assert_eq!(*v, search_path(&value, path.as_view_path()).unwrap());
+        }
+
+        let paths = search_all(&value, ViewPath::parse_str("a..[0]").unwrap());
+        assert_eq!(
+            vec![(ViewPathBuf::parse_str("a.b[0]").unwrap(), &json!({"id": 2}))],
+            paths
+        );
+        assert_eq!(
+            2,
+            search_all(&value, ViewPath::parse_str("a.b..id").unwrap()).len()
+        );
+    }
+
+    #[test]
+    fn test_search_all_slice() {
+        let value = json!({"logs": [0, 1, 2, 3, 4, 5], "obj": {"a": 1}});
+        let search = |p| {
+            search_all(&value, ViewPath::parse_str(p).unwrap())
+                .into_iter()
+                .map(|(path, v)| (path.to_string(), v.as_i64().unwrap()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![("logs[0]".to_string(), 0), ("logs[1]".to_string(), 1)],
+            search("logs[:2]")
+        );
+        assert_eq!(
+            vec![1, 3, 5],
+            search("logs[1::2]")
+                .into_iter()
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![5, 4],
+            search("logs[:-3:-1]")
+                .into_iter()
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>()
+        );
+        assert!(search("logs[10:]").is_empty());
+        assert!(search("obj[:]").is_empty());
+    }
+
+    #[test]
+    fn test_search_all_filter() {
+        let value = json!({
+            "services": [
+                {"id": 1, "status": "active", "port": 80, "tags": ["web"]},
+                {"id": 2, "status": "stopped", "port": 8080},
+                {"id": 3, "status": "active", "port": 9000.5, "owner": {"name": "ops"}},
+                {"id": 4, "port": "none"},
+            ],
+            "envs": {"dev": {"debug": true}, "prod": {"debug": false}},
+        });
+        let ids = |p| {
+            search_all(&value, ViewPath::parse_str(p).unwrap())
+                .into_iter()
+                .map(|(path, v)| (path.to_string(), v.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                ("services[0].id".to_string(), json!(1)),
+                ("services[2].id".to_string(), json!(3)),
+            ],
+            ids(r#"services[?(@.status == "active")].id"#)
+        );
+        assert_eq!(
+            vec![json!(2), json!(4)],
+            ids(r#"services[?(@.status != "active")].id"#)
+                .into_iter()
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![json!(2), json!(3)],
+            ids("services[?(@.port > 80 && @.port <= 9000.5)].id")
+                .into_iter()
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![json!(1), json!(3)],
+            ids(r#"services[?(@.tags || @.owner.name =~ "^o")].id"#)
+                .into_iter()
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![json!(4)],
+            ids("services[?(!@.status)].id")
+                .into_iter()
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![("envs.dev".to_string(), json!({"debug": true}))],
+            ids("envs[?(@.debug == true)]")
+        );
+        assert!(ids(r#"services[?(@.owner.name =~ @.id)]"#).is_empty());
+    }
+
+    #[test]
+    fn test_search_compiled_path() {
+        let value = json!({"a": {"b": [1, {"c": 2}], "d": 3}});
+        let mut cache = PathCache::new(4);
+        for path in ["a.b[-1].c", "a.(x | d)", "$"] {
+            let compiled = cache.get(path).unwrap();
+            assert_eq!(
+                search_path(&value, ViewPath::parse_str(path).unwrap()).unwrap(),
+                search_path(&value, &*compiled).unwrap()
+            );
+        }
+        let compiled = CompiledPath::compile("a.b[*]").unwrap();
+        assert_eq!(
+            search_all(&value, ViewPath::parse_str("a.b[*]").unwrap()),
+            search_all(&value, &compiled)
+        );
+        assert_eq!(
+            "`x` is not found at `a`",
+            search_path(&value, &CompiledPath::compile("a.x").unwrap())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    /// A typed tree, to check the lookups do not depend on `Value`.
+    #[derive(Debug, PartialEq)]
+    enum Tree {
+        Leaf(i64),
+        List(Vec<Tree>),
+        Map(Vec<(String, Tree)>),
+    }
+
+    impl Navigable for Tree {
+        fn get_field(&self, name: &str) -> Option<&Self> {
+            match self {
+                Tree::Map(m) => m.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+                _ => None,
+            }
+        }
+
+        // Looks the keys up by their hash alone, as a map keyed by it would.
+        fn get_field_hashed(&self, _: &str, hash: u64) -> Option<&Self> {
+            match self {
+                Tree::Map(m) => m
+                    .iter()
+                    .find(|(k, _)| view::field_hash(k) == hash)
+                    .map(|(_, v)| v),
+                _ => None,
+            }
+        }
+
+        fn get_index(&self, index: usize) -> Option<&Self> {
+            match self {
+                Tree::List(l) => l.get(index),
+                _ => None,
+            }
+        }
+
+        fn len(&self) -> Option<usize> {
+            match self {
+                Tree::Leaf(_) => None,
+                Tree::List(l) => Some(l.len()),
+                Tree::Map(m) => Some(m.len()),
+            }
+        }
+
+        fn children(&self) -> Box<dyn Iterator<Item = (SegmentBuf, &Self)> + '_> {
+            match self {
+                Tree::Leaf(_) => Box::new(std::iter::empty()),
+                Tree::List(l) => Box::new(
+                    l.iter()
+                        .enumerate()
+                        .map(|(i, v)| (SegmentBuf::index(i as isize), v)),
+                ),
+                Tree::Map(m) => Box::new(m.iter().map(|(k, v)| (SegmentBuf::from(k.as_str()), v))),
+            }
+        }
+
+        fn as_leaf(&self) -> Option<Leaf<'_>> {
+            match self {
+                Tree::Leaf(n) => Some(Leaf::Number(n.to_string().into())),
+                _ => None,
+            }
+        }
+
+        fn is_array(&self) -> bool {
+            matches!(self, Tree::List(_))
+        }
+
+        fn is_map(&self) -> bool {
+            matches!(self, Tree::Map(_))
+        }
+
+        fn type_name(&self) -> &'static str {
+            match self {
+                Tree::Leaf(_) => "number",
+                Tree::List(_) => "list",
+                Tree::Map(_) => "map",
+            }
+        }
+
+        fn map_type_name() -> &'static str {
+            "map"
+        }
+
+        fn array_type_name() -> &'static str {
+            "list"
+        }
+    }
+
+    #[test]
+    fn test_search_navigable() {
+        let tree = Tree::Map(vec![
+            (
+                "a".to_string(),
+                Tree::List(vec![Tree::Leaf(1), Tree::Leaf(2)]),
+            ),
+            (
+                "b".to_string(),
+                Tree::Map(vec![("c".to_string(), Tree::Leaf(2))]),
+            ),
+        ]);
+        let path = |p| ViewPath::parse_str(p).unwrap();
+        assert_eq!(&Tree::Leaf(2), search_path(&tree, path("a[-1]")).unwrap());
+        assert_eq!(
+            "expected list at `b`, found map",
+            search_path(&tree, path("b[0]")).unwrap_err().to_string()
+        );
+        assert_eq!(
+            vec!["a[1]", "b.c"],
+            search_all(&tree, path("..[?(@ == 2)]"))
+                .into_iter()
+                .map(|(p, _)| p.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(vec![
+                ViewPathBuf::parse_str("a[1]").unwrap(),
+                ViewPathBuf::parse_str("b.c").unwrap()
+            ]),
+            nest_find_value(&tree, "2")
+        );
+
+        // The compiled paths look the fields up by their hashes.
+        let compiled = CompiledPath::compile("b.(x | c)").unwrap();
+        assert_eq!(&Tree::Leaf(2), search_path(&tree, &compiled).unwrap());
+        let hashes = [view::field_hash("b")];
+        let seg = Hashed(Segment::from("a"), &hashes);
+        assert!(matches!(search_path(&tree, [seg]), Ok(Tree::Map(_))));
+    }
+
+    /// A segment whose field hashes are given, to check they are used.
+    struct Hashed<'a>(Segment<'a>, &'a [u64]);
+
+    impl<'a> std::borrow::Borrow<Segment<'a>> for Hashed<'a> {
+        fn borrow(&self) -> &Segment<'a> {
+            &self.0
+        }
+    }
+
+    impl<'a> SegmentRef<'a> for Hashed<'a> {
+        fn field_hashes(&self) -> &[u64] {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_resolve_pointer() {
+        let value = json!({"a": {"1": "x", "01": "y"}, "b": [0, {"2": true}]});
+        let resolve = |p| resolve_pointer(&value, &ViewPathBuf::from_json_pointer(p).unwrap());
+        for (pointer, path, expected) in [
+            ("/a/1", r#"a."1""#, json!("x")),
+            ("/a/01", r#"a."01""#, json!("y")),
+            ("/b/1/2", r#"b[1]."2""#, json!(true)),
+            ("", "$", value.clone()),
+        ] {
+            let resolved = resolve(pointer);
+            assert_eq!(path, resolved.to_string());
+            assert_eq!(
+                &expected,
+                search_path(&value, resolved.as_view_path()).unwrap()
+            );
+        }
+        assert_eq!(
+            r#"`"2"` is not found at `a`"#,
+            search_path(&value, resolve("/a/2").as_view_path())
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(r#"a."2"[0].c"#, resolve("/a/2/0/c").to_string());
+        assert_eq!(
+            "index 2 is out of range at `b`, the array has 2 elements",
+            search_path(&value, resolve("/b/2").as_view_path())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_search_path_errors() {
+        let value = json!({"a": {"b": [1, "s"]}});
+        let search = |p| search_path(&value, ViewPath::parse_str(p).unwrap()).unwrap_err();
+        match search("a.c") {
+            Error::MissingKey { path, segment } => {
+                assert_eq!(ViewPathBuf::from("a"), path);
+                assert_eq!("c", segment.to_string());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match search("a.b[3]") {
+            Error::IndexOutOfRange { path, index, len } => {
+                assert_eq!("a.b", path.to_string());
+                assert_eq!((3, 2), (index, len));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match search("a.b[-1].x") {
+            Error::TypeMismatch {
+                path,
+                expected,
+                found,
+            } => {
+                assert_eq!("a.b[1]", path.to_string());
+                assert_eq!(("object", "string"), (expected, found));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            "expected array at `a`, found object",
+            search("a[0]").to_string()
+        );
+
+        // The compiled path builds the same prefix on failure.
+        for p in ["a.c", "a.b[3]", "a.b[-1].x", "a[0]", "a.(x|b)[-2].y"] {
+            let compiled = view::CompiledPath::compile(p).unwrap();
+            assert_eq!(
+                search(p).to_string(),
+                search_path(&value, &compiled).unwrap_err().to_string()
+            );
+        }
+        assert_eq!(
+            "expected object at `a.b[0]`, found number",
+            search("a.(x|b)[-2].y").to_string()
+        );
+    }
 }
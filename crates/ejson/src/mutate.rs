@@ -0,0 +1,342 @@
+//! The mutable counterpart of the lookup functions.
+
+use std::mem;
+
+use serde_json::{Map, Value};
+use view::{FieldBuf, Segment, SegmentBuf, ViewPath, ViewPathBuf};
+
+use crate::{mismatch, missing, out_of_range, resolve_index, Error};
+
+pub fn search_path_mut<'a>(
+    value: &'a mut Value,
+    path: ViewPath<'_>,
+) -> Result<&'a mut Value, Error> {
+    let segments = path.into_iter().collect::<Vec<_>>();
+    walk_mut(value, &segments, None, false, &mut ViewPathBuf::root())
+}
+
+/// Sets the value at the path and returns the previous one, if any.
+///
+/// The last segment may add a new key to an object or append to an array
+/// when it indexes right after the last element. If `create` is set, the
+/// missing intermediate values are created too, as objects or as arrays
+/// depending on the segment that follows them, and `null`s are replaced.
+pub fn set_path(
+    value: &mut Value,
+    path: ViewPath<'_>,
+    new: Value,
+    create: bool,
+) -> Result<Option<Value>, Error> {
+    let segments = path.into_iter().collect::<Vec<_>>();
+    set_segments(value, &segments, new, create)
+}
+
+fn set_segments(
+    value: &mut Value,
+    segments: &[Segment<'_>],
+    new: Value,
+    create: bool,
+) -> Result<Option<Value>, Error> {
+    let (last, parents) = match segments.split_last() {
+        Some(s) => s,
+        None => return Ok(Some(mem::replace(value, new))),
+    };
+    let mut prefix = ViewPathBuf::root();
+    let parent = walk_mut(value, parents, Some(last), create, &mut prefix)?;
+    prepare(parent, last, create);
+    match (last, parent) {
+        (Segment::Field(_) | Segment::Coalesce(_), Value::Object(m)) => {
+            let key = existing_key(m, last).unwrap_or_else(|| first_key(last));
+            Ok(m.insert(key, new))
+        }
+        (Segment::Index(i), Value::Array(a)) => match resolve_index(*i, a.len()) {
+            Some(i) => Ok(Some(mem::replace(&mut a[i], new))),
+            None if *i as usize == a.len() => {
+                a.push(new);
+                Ok(None)
+            }
+            None => Err(out_of_range(prefix, *i, a.len())),
+        },
+        (last, parent) => Err(mismatch(prefix, last, parent)),
+    }
+}
+
+/// Inserts the value at the path, shifting the following elements if the
+/// last segment is an index. Intermediate values are created as with
+/// `set_path`.
+pub fn insert_path(
+    value: &mut Value,
+    path: ViewPath<'_>,
+    new: Value,
+    create: bool,
+) -> Result<(), Error> {
+    let segments = path.into_iter().collect::<Vec<_>>();
+    let (last, parents) = match segments.split_last() {
+        Some((last @ Segment::Index(_), parents)) => (last, parents),
+        _ => return set_segments(value, &segments, new, create).map(|_| ()),
+    };
+    let mut prefix = ViewPathBuf::root();
+    let parent = walk_mut(value, parents, Some(last), create, &mut prefix)?;
+    prepare(parent, last, create);
+    match (last, parent) {
+        (Segment::Index(i), Value::Array(a)) => {
+            match resolve_index(*i, a.len()) {
+                Some(i) => a.insert(i, new),
+                None if *i as usize == a.len() => a.push(new),
+                None => return Err(out_of_range(prefix, *i, a.len())),
+            }
+            Ok(())
+        }
+        (last, parent) => Err(mismatch(prefix, last, parent)),
+    }
+}
+
+/// Removes the value at the path and returns it.
+///
+/// Removing the root leaves `null` behind.
+pub fn remove_path(value: &mut Value, path: ViewPath<'_>) -> Result<Value, Error> {
+    let segments = path.into_iter().collect::<Vec<_>>();
+    let (last, parents) = match segments.split_last() {
+        Some(s) => s,
+        None => return Ok(mem::take(value)),
+    };
+    let mut prefix = ViewPathBuf::root();
+    let parent = walk_mut(value, parents, None, false, &mut prefix)?;
+    match (last, parent) {
+        (Segment::Field(_) | Segment::Coalesce(_), Value::Object(m)) => {
+            match existing_key(m, last) {
+                Some(key) => Ok(m.shift_remove(&key).unwrap_or_default()),
+                None => Err(missing(prefix, last)),
+            }
+        }
+        (Segment::Index(i), Value::Array(a)) => match resolve_index(*i, a.len()) {
+            Some(i) => Ok(a.remove(i)),
+            None => Err(out_of_range(prefix, *i, a.len())),
+        },
+        (last, parent) => Err(mismatch(prefix, last, parent)),
+    }
+}
+
+/// Walks down the segments, `last` is the segment applied after them.
+fn walk_mut<'v>(
+    value: &'v mut Value,
+    segments: &[Segment<'_>],
+    last: Option<&Segment<'_>>,
+    create: bool,
+    prefix: &mut ViewPathBuf,
+) -> Result<&'v mut Value, Error> {
+    let mut value = value;
+    for (i, seg) in segments.iter().enumerate() {
+        let next = segments.get(i + 1).or(last);
+        prepare(value, seg, create);
+        value = match (seg, value) {
+            (Segment::Field(_) | Segment::Coalesce(_), Value::Object(m)) => {
+                let key = match existing_key(m, seg) {
+                    Some(key) => key,
+                    None if create => {
+                        let key = first_key(seg);
+                        m.insert(key.clone(), empty_for(next));
+                        key
+                    }
+                    None => return Err(missing(prefix.clone(), seg)),
+                };
+                prefix.push_back(SegmentBuf::Field(FieldBuf::new(key.as_str())));
+                m.get_mut(&key).unwrap()
+            }
+            (Segment::Index(i), Value::Array(a)) => {
+                let len = a.len();
+                let i = match resolve_index(*i, len) {
+                    Some(i) => i,
+                    None if create && *i as usize == len => {
+                        a.push(empty_for(next));
+                        len
+                    }
+                    None => return Err(out_of_range(prefix.clone(), *i, len)),
+                };
+                prefix.push_back(SegmentBuf::Index(i as isize));
+                &mut a[i]
+            }
+            (seg, value) => return Err(mismatch(prefix.clone(), seg, value)),
+        };
+    }
+    Ok(value)
+}
+
+/// Replaces a `null` with the container the segment expects, if `create`
+/// is set.
+fn prepare(value: &mut Value, seg: &Segment<'_>, create: bool) {
+    if create && value.is_null() {
+        *value = match seg {
+            Segment::Index(_) => Value::Array(Vec::new()),
+            _ => Value::Object(Map::new()),
+        }
+    }
+}
+
+fn empty_for(next: Option<&Segment<'_>>) -> Value {
+    match next {
+        Some(Segment::Index(_)) => Value::Array(Vec::new()),
+        Some(_) => Value::Object(Map::new()),
+        None => Value::Null,
+    }
+}
+
+/// Returns the key of the object matched by a field or coalesce segment.
+fn existing_key(m: &Map<String, Value>, seg: &Segment<'_>) -> Option<String> {
+    match seg {
+        Segment::Field(f) => m.contains_key(f.as_str()).then(|| f.as_str().to_string()),
+        Segment::Coalesce(c) => c
+            .iter()
+            .find(|f| m.contains_key(f.as_str()))
+            .map(|f| f.as_str().to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the key a field or coalesce segment creates.
+fn first_key(seg: &Segment<'_>) -> String {
+    match seg {
+        Segment::Field(f) => f.as_str().to_string(),
+        Segment::Coalesce(c) => c[0].as_str().to_string(),
+        _ => unreachable!("only fields and coalesces address object keys"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use view::{ViewPath, ViewPathBuf};
+
+    use crate::{insert_path, remove_path, search_path_mut, set_path, Error};
+
+    fn path(p: &str) -> ViewPath<'_> {
+        ViewPath::parse_str(p).unwrap()
+    }
+
+    #[test]
+    fn test_search_path_mut() {
+        let mut value = json!({"a": [1, {"b": 2}]});
+        *search_path_mut(&mut value, path("a[-1].b")).unwrap() = json!(3);
+        assert_eq!(json!({"a": [1, {"b": 3}]}), value);
+        assert!(matches!(
+            search_path_mut(&mut value, path("a[2]")),
+            Err(Error::IndexOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_set_path() {
+        let mut value = json!({"a": {"b": 1}, "l": [1, 2]});
+        assert_eq!(
+            Some(json!(1)),
+            set_path(&mut value, path("a.b"), json!(2), false).unwrap()
+        );
+        assert_eq!(
+            None,
+            set_path(&mut value, path("a.c"), json!(3), false).unwrap()
+        );
+        assert_eq!(
+            Some(json!(2)),
+            set_path(&mut value, path("l[-1]"), json!(4), false).unwrap()
+        );
+        assert_eq!(
+            None,
+            set_path(&mut value, path("l[2]"), json!(5), false).unwrap()
+        );
+        assert_eq!(
+            Some(json!(2)),
+            set_path(&mut value, path("(x | a).b"), json!(6), false).unwrap()
+        );
+        assert_eq!(
+            Some(json!(6)),
+            set_path(&mut value, path("a.(x | b)"), json!(7), false).unwrap()
+        );
+        assert_eq!(json!({"a": {"b": 7, "c": 3}, "l": [1, 4, 5]}), value);
+
+        assert_eq!(
+            Some(value.clone()),
+            set_path(&mut value, ViewPath::root(), json!(0), false).unwrap()
+        );
+        assert_eq!(json!(0), value);
+    }
+
+    #[test]
+    fn test_set_path_create() {
+        let mut value = json!({"n": null});
+        assert!(matches!(
+            set_path(&mut value, path("a.b[0].c"), json!(1), false),
+            Err(Error::MissingKey { .. })
+        ));
+        set_path(&mut value, path("a.b[0].c"), json!(1), true).unwrap();
+        set_path(&mut value, path("n[0][0]"), json!(2), true).unwrap();
+        assert_eq!(json!({"a": {"b": [{"c": 1}]}, "n": [[2]]}), value);
+    }
+
+    #[test]
+    fn test_set_path_errors() {
+        let mut value = json!({"s": "str", "l": [1]});
+        match set_path(&mut value, path("s.x"), json!(1), true) {
+            Err(Error::TypeMismatch {
+                path,
+                expected,
+                found,
+            }) => {
+                assert_eq!(ViewPathBuf::from("s"), path);
+                assert_eq!("object", expected);
+                assert_eq!("string", found);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match set_path(&mut value, path("l[3]"), json!(1), true) {
+            Err(Error::IndexOutOfRange { path, index, len }) => {
+                assert_eq!(ViewPathBuf::from("l"), path);
+                assert_eq!((3, 1), (index, len));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            set_path(&mut value, path("l[*]"), json!(1), true),
+            Err(Error::NotSingular { .. })
+        ));
+        assert!(matches!(
+            set_path(&mut value, path("l.x"), json!(1), true),
+            Err(Error::TypeMismatch {
+                expected: "object",
+                found: "array",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_insert_path() {
+        let mut value = json!({"l": [1, 3]});
+        insert_path(&mut value, path("l[1]"), json!(2), false).unwrap();
+        insert_path(&mut value, path("l[3]"), json!(4), false).unwrap();
+        insert_path(&mut value, path("l[-4]"), json!(0), false).unwrap();
+        insert_path(&mut value, path("m[0]"), json!(0), true).unwrap();
+        insert_path(&mut value, path("k"), json!("v"), false).unwrap();
+        assert_eq!(json!({"k": "v", "l": [0, 1, 2, 3, 4], "m": [0]}), value);
+        assert!(insert_path(&mut value, path("l[6]"), json!(6), false).is_err());
+    }
+
+    #[test]
+    fn test_remove_path() {
+        let mut value = json!({"a": {"b": 1, "c": 2}, "l": [1, 2, 3]});
+        assert_eq!(json!(1), remove_path(&mut value, path("a.b")).unwrap());
+        assert_eq!(json!(3), remove_path(&mut value, path("l[-1]")).unwrap());
+        assert_eq!(json!({"a": {"c": 2}, "l": [1, 2]}), value);
+        assert!(matches!(
+            remove_path(&mut value, path("a.b")),
+            Err(Error::MissingKey { .. })
+        ));
+        assert!(matches!(
+            remove_path(&mut value, path("l[2]")),
+            Err(Error::IndexOutOfRange { .. })
+        ));
+        assert!(matches!(
+            remove_path(&mut value, path("x.y")),
+            Err(Error::MissingKey { .. })
+        ));
+    }
+}
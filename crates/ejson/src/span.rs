@@ -0,0 +1,285 @@
+//! Locates the values of a json text, which `serde_json::Value` forgets.
+
+use std::{borrow::Cow, collections::HashMap, fmt};
+
+use view::{FieldBuf, SegmentBuf, ViewPathBuf};
+
+use crate::Error;
+
+/// The position of a value in a json text. The line and the column are
+/// 1-based and the column counts bytes, as editors expect from `--vimgrep`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The locations of the values of a json text, by their concrete path, as
+/// returned by `search_all` or `nest_find_by`.
+///
+/// A duplicated key is located at its last occurrence, which is the one
+/// serde_json keeps.
+#[derive(Debug, Clone, Default)]
+pub struct SpanMap {
+    locations: HashMap<ViewPathBuf, Location>,
+}
+
+impl SpanMap {
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut scanner = Scanner {
+            text,
+            pos: 0,
+            line: 1,
+            line_start: 0,
+            path: ViewPathBuf::root(),
+            locations: HashMap::new(),
+        };
+        scanner.value()?;
+        scanner.skip_whitespace();
+        if scanner.pos < text.len() {
+            return Err(scanner.invalid());
+        }
+        Ok(Self {
+            locations: scanner.locations,
+        })
+    }
+
+    /// Returns the location of the value at the path.
+    pub fn get(&self, path: &ViewPathBuf) -> Option<Location> {
+        self.locations.get(path).copied()
+    }
+}
+
+/// Walks the text as serde_json does, recording where each value starts.
+/// Scalars are only skipped over, the text is meant to be valid json
+/// already.
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    path: ViewPathBuf,
+    locations: HashMap<ViewPathBuf, Location>,
+}
+
+impl<'a> Scanner<'a> {
+    fn location(&self) -> Location {
+        Location {
+            line: self.line,
+            column: self.pos - self.line_start + 1,
+        }
+    }
+
+    fn invalid(&self) -> Error {
+        Error::InvalidJson {
+            location: self.location(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            match b {
+                b'\n' => {
+                    self.pos += 1;
+                    self.line += 1;
+                    self.line_start = self.pos;
+                }
+                b' ' | b'\t' | b'\r' => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), Error> {
+        self.skip_whitespace();
+        if self.peek() != Some(b) {
+            return Err(self.invalid());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<(), Error> {
+        self.skip_whitespace();
+        self.locations.insert(self.path.clone(), self.location());
+        match self.peek() {
+            Some(b'{') => self.members(),
+            Some(b'[') => self.elements(),
+            Some(b'"') => self.string().map(|_| ()),
+            Some(_) => {
+                let len = self.text[self.pos..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)))
+                    .unwrap_or(self.text.len() - self.pos);
+                if len == 0 {
+                    return Err(self.invalid());
+                }
+                self.pos += len;
+                Ok(())
+            }
+            None => Err(self.invalid()),
+        }
+    }
+
+    fn members(&mut self) -> Result<(), Error> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            self.path
+                .push_back(SegmentBuf::field(FieldBuf::new(key.into_owned())));
+            self.value()?;
+            self.path.pop_back();
+            if self.next_item(b'}')? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn elements(&mut self) -> Result<(), Error> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(());
+        }
+        for i in 0.. {
+            self.path.push_back(SegmentBuf::index(i));
+            self.value()?;
+            self.path.pop_back();
+            if self.next_item(b']')? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes the comma before the next item, or the closing bracket and
+    /// then returns true.
+    fn next_item(&mut self, close: u8) -> Result<bool, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b',') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Some(b) if b == close => {
+                self.pos += 1;
+                Ok(true)
+            }
+            _ => Err(self.invalid()),
+        }
+    }
+
+    /// Consumes a string and returns its content, unescaped by serde_json
+    /// when needed.
+    fn string(&mut self) -> Result<Cow<'a, str>, Error> {
+        if self.peek() != Some(b'"') {
+            return Err(self.invalid());
+        }
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let mut escaped = false;
+        let mut i = start + 1;
+        loop {
+            match bytes.get(i) {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    escaped = true;
+                    i += 2;
+                }
+                Some(_) => i += 1,
+                None => return Err(self.invalid()),
+            }
+        }
+        self.pos = i + 1;
+        let raw = &self.text[start..self.pos];
+        if !escaped {
+            return Ok(Cow::Borrowed(&raw[1..raw.len() - 1]));
+        }
+        serde_json::from_str::<String>(raw)
+            .map(Cow::Owned)
+            .map_err(|_| Error::InvalidJson {
+                location: Location {
+                    line: self.line,
+                    column: start - self.line_start + 1,
+                },
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use view::{ViewPath, ViewPathBuf};
+
+    use super::{Location, SpanMap};
+
+    fn at(line: usize, column: usize) -> Option<Location> {
+        Some(Location { line, column })
+    }
+
+    #[test]
+    fn test_span_map() {
+        let text = r#"{
+  "a": {"b": [1, "x", null]},
+  "c d": [
+    {"e\"f": true}
+  ],
+  "g": -1.5e3
+}"#;
+        let spans = SpanMap::parse(text).unwrap();
+        let get = |path: &str| spans.get(&ViewPathBuf::parse_str(path).unwrap());
+        assert_eq!(at(1, 1), spans.get(&ViewPathBuf::root()));
+        assert_eq!(at(2, 8), get("a"));
+        assert_eq!(at(2, 15), get("a.b[0]"));
+        assert_eq!(at(2, 18), get("a.b[1]"));
+        assert_eq!(at(2, 23), get("a.b[2]"));
+        assert_eq!(at(3, 10), get(r#""c d""#));
+        assert_eq!(at(4, 5), get(r#""c d"[0]"#));
+        assert_eq!(at(4, 14), get(r#""c d"[0]."e\"f""#));
+        assert_eq!(at(6, 8), get("g"));
+        assert_eq!(None, get("a.b[3]"));
+    }
+
+    #[test]
+    fn test_span_map_search_all() {
+        let text = "[{\"id\": 1}, {\"id\": 2, \"id\": 3},\n {\"id\": 4}]";
+        let value = serde_json::from_str::<Value>(text).unwrap();
+        let spans = SpanMap::parse(text).unwrap();
+        let locations = crate::search_all(&value, ViewPath::parse_str("..id").unwrap())
+            .into_iter()
+            .map(|(path, v)| (spans.get(&path), v.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (at(1, 9), Value::from(1)),
+                (at(1, 29), Value::from(3)),
+                (at(2, 9), Value::from(4)),
+            ],
+            locations
+        );
+    }
+
+    #[test]
+    fn test_span_map_invalid() {
+        for text in ["", "[1,", "{\"a\" 1}", "[1] 2", "\"abc"] {
+            assert!(SpanMap::parse(text).is_err(), "{}", text);
+        }
+    }
+}
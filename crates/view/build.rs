@@ -2,6 +2,7 @@ extern crate lalrpop;
 
 fn main() {
     println!("cargo:rerun-if-changed=src/path.lalrpop");
+    println!("cargo:rerun-if-changed=src/jsonpath.lalrpop");
 
     lalrpop::Configuration::new()
         .always_use_colors()
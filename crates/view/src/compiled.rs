@@ -0,0 +1,286 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::Arc,
+};
+
+use crate::{Segment, ViewPath, ViewPathBuf};
+
+/// Returns the hash of a field name that compiled paths precompute, the
+/// 64-bit FNV-1a of its bytes. It does not depend on the process, so a
+/// document may key its maps by it.
+pub fn field_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// SegmentRef is a segment being evaluated, along with the hashes of its
+/// fields if they were precomputed.
+pub trait SegmentRef<'p>: Borrow<Segment<'p>> {
+    /// Returns the `field_hash` of each field of a field or coalesce
+    /// segment, or nothing if they were not precomputed.
+    fn field_hashes(&self) -> &[u64] {
+        &[]
+    }
+}
+
+impl<'p> SegmentRef<'p> for Segment<'p> {}
+
+impl<'p> SegmentRef<'p> for &Segment<'p> {}
+
+/// CompiledSegment is a segment of a compiled path, with the hashes of its
+/// fields.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CompiledSegment {
+    segment: Segment<'static>,
+    hashes: Box<[u64]>,
+}
+
+impl CompiledSegment {
+    pub fn segment(&self) -> &Segment<'static> {
+        &self.segment
+    }
+}
+
+impl From<Segment<'static>> for CompiledSegment {
+    fn from(segment: Segment<'static>) -> Self {
+        let hashes = match &segment {
+            Segment::Field(f) => vec![field_hash(f.as_str())],
+            Segment::Coalesce(c) => c.iter().map(|f| field_hash(f.as_str())).collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            segment,
+            hashes: hashes.into(),
+        }
+    }
+}
+
+impl Borrow<Segment<'static>> for &CompiledSegment {
+    fn borrow(&self) -> &Segment<'static> {
+        &self.segment
+    }
+}
+
+impl SegmentRef<'static> for &CompiledSegment {
+    fn field_hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+}
+
+/// CompiledPath is a path parsed once, to be evaluated many times.
+///
+/// The segments own their fields and sit in a contiguous slice, so a
+/// successful `search_path` over them neither parses nor allocates. The
+/// hashes of the fields are precomputed by `field_hash`, the documents
+/// whose maps are keyed by it look the fields up without hashing them, see
+/// `Navigable::get_field_hashed`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CompiledPath {
+    segments: Box<[CompiledSegment]>,
+    singular: bool,
+}
+
+impl CompiledPath {
+    pub fn compile(input: &str) -> Result<Self, crate::Error> {
+        ViewPath::parse_str(input).map(Self::from)
+    }
+
+    pub fn segments(&self) -> &[CompiledSegment] {
+        &self.segments
+    }
+
+    /// Returns true if the path addresses at most one value.
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+
+    pub fn to_view_path_buf(&self) -> ViewPathBuf {
+        self.segments
+            .iter()
+            .map(|s| s.segment.to_segment_buf())
+            .collect::<VecDeque<_>>()
+            .into()
+    }
+}
+
+impl fmt::Display for CompiledPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_view_path_buf().fmt(f)
+    }
+}
+
+impl<'a> From<ViewPath<'a>> for CompiledPath {
+    fn from(path: ViewPath<'a>) -> Self {
+        let singular = path.is_singular();
+        Self {
+            segments: path
+                .into_owned()
+                .into_iter()
+                .map(CompiledSegment::from)
+                .collect(),
+            singular,
+        }
+    }
+}
+
+impl From<&ViewPathBuf> for CompiledPath {
+    fn from(path: &ViewPathBuf) -> Self {
+        Self::from(path.as_view_path())
+    }
+}
+
+impl<'p> IntoIterator for &'p CompiledPath {
+    type Item = &'p CompiledSegment;
+    type IntoIter = std::slice::Iter<'p, CompiledSegment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.segments.iter()
+    }
+}
+
+/// PathCache keeps the most recently used compiled paths, keyed by their
+/// text.
+///
+/// It is meant for the handful of paths a service evaluates over and over,
+/// once full the least recently used path is evicted.
+#[derive(Debug)]
+pub struct PathCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (Arc<CompiledPath>, u64)>,
+}
+
+impl PathCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the compiled path of `input`, compiling it on a miss.
+    ///
+    /// Paths that fail to compile are not cached.
+    pub fn get(&mut self, input: &str) -> Result<Arc<CompiledPath>, crate::Error> {
+        self.tick += 1;
+        if let Some((path, used)) = self.entries.get_mut(input) {
+            *used = self.tick;
+            return Ok(path.clone());
+        }
+        let path = Arc::new(CompiledPath::compile(input)?);
+        if self.capacity == 0 {
+            return Ok(path);
+        }
+        if self.entries.len() >= self.capacity {
+            // The capacity is expected to be small, so scanning for the
+            // least recently used entry is cheaper than keeping an order.
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(k, _)| k.clone());
+            if let Some(lru) = lru {
+                self.entries.remove(&lru);
+            }
+        }
+        self.entries
+            .insert(input.to_string(), (path.clone(), self.tick));
+        Ok(path)
+    }
+
+    pub fn contains(&self, input: &str) -> bool {
+        self.entries.contains_key(input)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{field_hash, CompiledSegment, SegmentRef};
+    use crate::{CompiledPath, PathCache, Segment, ViewPath, ViewPathBuf};
+
+    #[test]
+    fn test_compiled_path() {
+        let path = CompiledPath::compile(r#"a.(b | c)[0]."d e""#).unwrap();
+        assert_eq!(4, path.segments().len());
+        assert!(path.is_singular());
+        assert!(path.segments()[1].segment().is_coalesce());
+        assert_eq!(r#"a.(b | c)[0]."d e""#, path.to_string());
+        assert_eq!(
+            ViewPathBuf::parse_str(r#"a.(b | c)[0]."d e""#).unwrap(),
+            path.to_view_path_buf()
+        );
+        assert_eq!(
+            path,
+            CompiledPath::from(ViewPath::parse_str(r#"a.(b|c)[0]."d e""#).unwrap())
+        );
+        assert!(!CompiledPath::compile("a.*").unwrap().is_singular());
+        assert_eq!(
+            vec![&Segment::descendant(), &Segment::from("id")],
+            (&CompiledPath::compile("..id").unwrap())
+                .into_iter()
+                .map(CompiledSegment::segment)
+                .collect::<Vec<_>>()
+        );
+        assert!(CompiledPath::compile("a.[").is_err());
+
+        let path = CompiledPath::compile("a.(b | c)[0]").unwrap();
+        let hashes = (&path)
+            .into_iter()
+            .map(|s| s.field_hashes().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                vec![field_hash("a")],
+                vec![field_hash("b"), field_hash("c")],
+                vec![]
+            ],
+            hashes
+        );
+        assert_eq!(0xaf63_dc4c_8601_ec8c, field_hash("a"));
+    }
+
+    #[test]
+    fn test_path_cache() {
+        let mut cache = PathCache::new(2);
+        let a = cache.get("a").unwrap();
+        assert!(Arc::ptr_eq(&a, &cache.get("a").unwrap()));
+        cache.get("b").unwrap();
+        assert_eq!(2, cache.len());
+
+        // `a` is more recent than `b`, so `b` is evicted.
+        cache.get("a").unwrap();
+        cache.get("c").unwrap();
+        assert_eq!(2, cache.len());
+        assert!(cache.contains("a") && cache.contains("c"));
+        assert!(!cache.contains("b"));
+
+        assert!(cache.get("[").is_err());
+        assert!(!cache.contains("["));
+
+        let mut cache = PathCache::new(0);
+        assert!(cache.get("a").is_ok());
+        assert!(cache.is_empty());
+    }
+}
@@ -1,30 +1,106 @@
 use std::{
-    fmt::{self, Display},
+    borrow::Cow,
     cell::RefCell,
+    fmt::{self, Display, Write},
 };
 
 use regex::Regex;
 
 thread_local! {
     static VALID_FIELD: RefCell<Regex> =
-       RefCell::new( Regex::new("^[0-9]*[a-zA-Z_][0-9a-zA-Z_]*$").unwrap());
+       RefCell::new( Regex::new(r"^[0-9]*[\p{XID_Start}_]\p{XID_Continue}*$").unwrap());
 }
 
-/// A valid fieldname can contain alphanumeric characters and an underscore.
-/// It may start with a number, but has to consist of more than just a number.
-/// Fields that have other characters can be used, but need to be quoted.
+/// A valid fieldname is a Unicode identifier, made of letters, digits and
+/// underscores. It may start with a number, but has to consist of more than
+/// just a number. Fields that have other characters, or are empty, can be
+/// used, but need to be quoted.
 pub(crate) fn is_valid_field_name(name: &str) -> bool {
     VALID_FIELD.with(|r| r.borrow().is_match(name))
 }
 
+/// Resolves the escapes of the content of a quoted string: `\"`, `\\`,
+/// `\n`, `\r`, `\t` and `\u{...}`. Any other escape is rejected, so that a
+/// backslash is never dropped silently.
+pub(crate) fn unescape(s: &str) -> Result<Cow<'_, str>, &'static str> {
+    if !s.contains('\\') {
+        return Ok(Cow::Borrowed(s));
+    }
+    let mut ret = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            ret.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(c @ ('"' | '\\')) => ret.push(c),
+            Some('n') => ret.push('\n'),
+            Some('r') => ret.push('\r'),
+            Some('t') => ret.push('\t'),
+            Some('u') => {
+                let rest = chars.as_str();
+                let (hex, rest) = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .ok_or("a unicode escape must be written `\\u{...}`")?;
+                let code = u32::from_str_radix(hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or("the unicode escape is not a char")?;
+                ret.push(code);
+                chars = rest.chars();
+            }
+            _ => return Err("unknown escape, a backslash must be escaped as `\\\\`"),
+        }
+    }
+    Ok(Cow::Owned(ret))
+}
+
+/// Writes the string quoted, escaping what `unescape` resolves.
+pub(crate) fn write_quoted(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Strips the quotes of a quoted field and resolves its escapes, or returns
+/// `None` if the name is not a valid quoted field.
+fn parse_quoted(name: &str) -> Option<Cow<'_, str>> {
+    if name.len() >= 2 && name.starts_with('\"') && name.ends_with('\"') {
+        unescape(&name[1..name.len() - 1]).ok()
+    } else {
+        None
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Field<'a> {
-    pub name: &'a str,
+    pub name: Cow<'a, str>,
     // This is a optimization to avoid having to scan for escapes.
     pub requires_quoting: bool,
 }
 
 impl<'a> Field<'a> {
+    /// Creates a field with the name as is, unlike `From` it does not
+    /// resolve quotes and escapes.
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            requires_quoting: !is_valid_field_name(name),
+            name: Cow::Borrowed(name),
+        }
+    }
+
     pub fn to_field_buf(&self) -> FieldBuf {
         FieldBuf {
             name: self.name.to_string(),
@@ -35,12 +111,20 @@ impl<'a> Field<'a> {
     pub fn as_str(&self) -> &str {
         &self.name
     }
+
+    /// Detaches the field from the string it borrows from.
+    pub fn into_owned(self) -> Field<'static> {
+        Field {
+            name: Cow::Owned(self.name.into_owned()),
+            requires_quoting: self.requires_quoting,
+        }
+    }
 }
 
 impl<'a> Display for Field<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.requires_quoting {
-            write!(f, r#""{}""#, self.name)
+            write_quoted(f, &self.name)
         } else {
             write!(f, r#"{}"#, self.name)
         }
@@ -48,30 +132,20 @@ impl<'a> Display for Field<'a> {
 }
 
 impl<'a> From<&'a str> for Field<'a> {
-    fn from(mut name: &'a str) -> Self {
-        let mut requires_quoting = false;
-
-        if name.starts_with('\"') && name.ends_with('\"') {
-            let len = name.len();
-            name = &name[1..len - 1];
-            requires_quoting = true;
-        } else if !is_valid_field_name(name) {
-            requires_quoting = true;
-        }
-
-        Self {
-            name,
-            requires_quoting,
+    fn from(name: &'a str) -> Self {
+        match parse_quoted(name) {
+            Some(name) => Self {
+                requires_quoting: !is_valid_field_name(&name),
+                name,
+            },
+            None => Self::new(name),
         }
     }
 }
 
 impl<'a> From<&'a FieldBuf> for Field<'a> {
     fn from(fb: &'a FieldBuf) -> Self {
-        Self {
-            name: &fb.name,
-            requires_quoting: fb.requires_quoting,
-        }
+        fb.as_field()
     }
 }
 
@@ -84,13 +158,23 @@ pub struct FieldBuf {
 }
 
 impl FieldBuf {
+    /// Creates a field with the name as is, unlike `From` it does not
+    /// resolve quotes and escapes.
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            requires_quoting: !is_valid_field_name(&name),
+            name,
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         &self.name
     }
 
     pub fn as_field(&self) -> Field<'_> {
         Field {
-            name: &self.name,
+            name: Cow::Borrowed(&self.name),
             requires_quoting: self.requires_quoting,
         }
     }
@@ -98,31 +182,15 @@ impl FieldBuf {
 
 impl Display for FieldBuf {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.requires_quoting {
-            write!(f, r#""{}""#, self.name)
-        } else {
-            write!(f, r#"{}"#, self.name)
-        }
+        self.as_field().fmt(f)
     }
 }
 
 impl From<String> for FieldBuf {
-    fn from(mut name: String) -> Self {
-        let mut requires_quoting = false;
-
-        if name.starts_with('\"') && name.ends_with('\"') {
-            // There is unfortunately no way to make an owned substring of a string.
-            // So we have to take a slice and clone it.
-            let len = name.len();
-            name = name[1..len - 1].to_string();
-            requires_quoting = true;
-        } else if !is_valid_field_name(&name) {
-            requires_quoting = true
-        }
-
-        Self {
-            name,
-            requires_quoting,
+    fn from(name: String) -> Self {
+        match parse_quoted(&name) {
+            Some(unquoted) => Self::new(unquoted),
+            None => Self::new(name),
         }
     }
 }
@@ -136,31 +204,25 @@ impl From<&str> for FieldBuf {
 #[cfg(any(test, feature = "arbitrary"))]
 impl quickcheck::Arbitrary for FieldBuf {
     fn arbitrary(g: &mut quickcheck::Gen) -> Self {
-        let chars = (32u8..90).map(|c| c as char).collect::<Vec<_>>();
-        let len = u32::arbitrary(g) % 100 + 1;
+        let chars = (32u8..127)
+            .map(|c| c as char)
+            .chain(['\n', '\t', '\r', '\u{1}', 'é', '名', '🦀'])
+            .collect::<Vec<_>>();
+        let len = u32::arbitrary(g) % 100;
         let name = (0..len)
             .map(|_| chars[usize::arbitrary(g) % chars.len()])
-            .collect::<String>()
-            .replace(r#"""#, r#"\""#);
-        FieldBuf::from(name)
+            .collect::<String>();
+        FieldBuf::new(name)
     }
 
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(
-            self.name
-                .shrink()
-                .filter(|name| !name.is_empty())
-                .map(|name| {
-                    let name = name.replace(r#"""#, r#"/""#);
-                    FieldBuf::from(name)
-                }),
-        )
+        Box::new(self.name.shrink().map(FieldBuf::new))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::FieldBuf;
+    use crate::{Field, FieldBuf, ViewPathBuf};
 
     #[test]
     fn test_field_buf_quoted() {
@@ -174,10 +236,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_field_escapes() {
+        let field = Field::from(r#""a\"b\\c\nd\u{e9}""#);
+        assert_eq!("a\"b\\c\nd\u{e9}", field.as_str());
+        assert!(field.requires_quoting);
+
+        let field = FieldBuf::new("a\"b\\c\nd\u{1}é");
+        assert_eq!(r#""a\"b\\c\nd\u{1}é""#, field.to_string());
+        assert_eq!(field, FieldBuf::from(field.to_string()));
+
+        assert_eq!("a.b", Field::from(r#""a.b""#).as_str());
+        assert_eq!(r#""\"x\"""#, FieldBuf::new(r#""x""#).to_string());
+
+        // Unknown or invalid escapes are not a quoted field, the name is kept
+        // as is.
+        assert_eq!(r#""a\b""#, Field::from(r#""a\b""#).as_str());
+        assert_eq!(r#""\u{zz}""#, Field::from(r#""\u{zz}""#).as_str());
+    }
+
+    #[test]
+    fn test_field_redundant_quotes() {
+        assert_eq!(Field::new("x"), Field::from(r#""x""#));
+        assert_eq!(FieldBuf::new("x"), FieldBuf::from(r#""x""#));
+        assert_eq!("x", FieldBuf::from(r#""x""#).to_string());
+        assert_eq!(
+            ViewPathBuf::parse_str("x.y").unwrap(),
+            ViewPathBuf::parse_str(r#""x".y"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_field_requires_quoting() {
+        for name in ["foo", "_", "1a", "名前", "café", "Ωmega_2", "true"] {
+            assert!(!FieldBuf::new(name).requires_quoting, "{}", name);
+        }
+        for name in ["", "1", "a-b", "a b", "a.b", "🦀", "-"] {
+            assert!(FieldBuf::new(name).requires_quoting, "{}", name);
+        }
+        assert_eq!(r#""""#, FieldBuf::new("").to_string());
+        assert_eq!("", Field::from(r#""""#).as_str());
+    }
+
     #[test]
     fn test_field_buf_quickcheck() {
         fn test_field_buf(field_buf: FieldBuf) -> quickcheck::TestResult {
-            let field_buf2: FieldBuf = field_buf.name.as_str().into();
+            let field_buf2: FieldBuf = field_buf.to_string().into();
             assert_eq!(field_buf, field_buf2);
             quickcheck::TestResult::passed()
         }
@@ -0,0 +1,203 @@
+use std::{cmp::Ordering, fmt, hash};
+
+use crate::{field::write_quoted, SegmentBuf, ViewPathBuf};
+
+/// Filter is the predicate of a `[?(...)]` segment.
+///
+/// The paths of a filter are relative to the value being tested, which is
+/// written `@` in the path syntax.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Filter {
+    /// True if the relative path matches anything.
+    Exists(ViewPathBuf),
+    Compare(Operand, CompareOp, Operand),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    pub fn exists(path: ViewPathBuf) -> Self {
+        Self::Exists(path)
+    }
+
+    pub fn compare(left: Operand, op: CompareOp, right: Operand) -> Self {
+        Self::Compare(left, op, right)
+    }
+
+    pub fn and(left: Filter, right: Filter) -> Self {
+        Self::And(Box::new(left), Box::new(right))
+    }
+
+    pub fn or(left: Filter, right: Filter) -> Self {
+        Self::Or(Box::new(left), Box::new(right))
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(filter: Filter) -> Self {
+        Self::Not(Box::new(filter))
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Filter::Or(..) => 0,
+            Filter::And(..) => 1,
+            Filter::Compare(..) => 2,
+            Filter::Exists(_) | Filter::Not(_) => 3,
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Wraps the operand of a binary operator in parentheses if it would
+        // otherwise parse back with another grouping.
+        let group = |f: &mut fmt::Formatter<'_>, filter: &Filter, min: u8| {
+            if filter.precedence() < min {
+                write!(f, "({})", filter)
+            } else {
+                write!(f, "{}", filter)
+            }
+        };
+        match self {
+            Filter::Exists(path) => write!(f, "{}", RelativePath(path)),
+            Filter::Compare(left, op, right) => write!(f, "{} {} {}", left, op, right),
+            Filter::Or(left, right) | Filter::And(left, right) => {
+                let precedence = self.precedence();
+                group(f, left, precedence)?;
+                f.write_str(if precedence == 0 { " || " } else { " && " })?;
+                group(f, right, precedence + 1)
+            }
+            Filter::Not(filter) => {
+                f.write_str("!")?;
+                group(f, filter, 3)
+            }
+        }
+    }
+}
+
+/// Operand is one side of a comparison in a `Filter`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Operand {
+    Path(ViewPathBuf),
+    Literal(Literal),
+    /// The string literal on the right side of `=~`, compiled once when the
+    /// path is parsed.
+    Pattern(Pattern),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Path(path) => write!(f, "{}", RelativePath(path)),
+            Operand::Literal(literal) => write!(f, "{}", literal),
+            Operand::Pattern(pattern) => write_quoted(f, pattern.as_str()),
+        }
+    }
+}
+
+/// Pattern is a compiled regex, compared and hashed by its text.
+#[derive(Debug, Clone)]
+pub struct Pattern(regex::Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        regex::Regex::new(pattern).map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl PartialOrd for Pattern {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pattern {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl hash::Hash for Pattern {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+/// Literal is a constant value in a `Filter`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Literal {
+    Null,
+    Bool(bool),
+    // The number is kept as written so the filter stays `Eq` and `Hash`.
+    Number(String),
+    String(String),
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Null => f.write_str("null"),
+            Literal::Bool(b) => write!(f, "{}", b),
+            Literal::Number(n) => f.write_str(n),
+            Literal::String(s) => write_quoted(f, s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// The left side matches the regex on the right side.
+    Match,
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Match => "=~",
+        })
+    }
+}
+
+/// Displays a path relative to `@`.
+struct RelativePath<'a>(&'a ViewPathBuf);
+
+impl<'a> fmt::Display for RelativePath<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.get(0) {
+            None => f.write_str("@"),
+            Some(SegmentBuf::Field(_) | SegmentBuf::Coalesce(_) | SegmentBuf::Wildcard) => {
+                write!(f, "@.{}", self.0)
+            }
+            _ => write!(f, "@{}", self.0),
+        }
+    }
+}
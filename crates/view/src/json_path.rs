@@ -0,0 +1,222 @@
+//! Conversions between `ViewPath` and JSONPath (RFC 9535).
+
+use std::{borrow::Cow, fmt::Write};
+
+use crate::{field::is_valid_field_name, Field, SegmentBuf, ViewPath, ViewPathBuf};
+
+impl<'a> ViewPath<'a> {
+    /// Parses a JSONPath like `$.store.book[0].title`.
+    pub fn parse_json_path(input: &'a str) -> Result<Self, crate::Error> {
+        crate::parser::parse_json_path(input).map_err(crate::Error::InvalidPath)
+    }
+}
+
+impl ViewPathBuf {
+    pub fn parse_json_path(input: &str) -> Result<Self, crate::Error> {
+        ViewPath::parse_json_path(input).map(|vp| vp.into_buf())
+    }
+
+    /// Renders the path as a normalized JSONPath, where every segment is
+    /// bracketed and names are single quoted, like `$['a'][0]`.
+    ///
+    /// JSONPath has no coalesce and a different filter syntax, so those
+    /// segments are rejected.
+    pub fn to_json_path(&self) -> Result<String, crate::Error> {
+        let mut path = String::from("$");
+        let mut segments = self.iter().peekable();
+        while let Some(segment) = segments.next() {
+            match segment {
+                SegmentBuf::Field(field) => write_name(&mut path, field.as_str()),
+                SegmentBuf::Index(i) => write!(path, "[{}]", i).unwrap(),
+                SegmentBuf::Wildcard => path.push_str("[*]"),
+                SegmentBuf::Slice(slice) => write!(path, "[{}]", slice).unwrap(),
+                // The descendant segment prefixes the selector following it.
+                SegmentBuf::Descendant if segments.peek().is_some() => path.push_str(".."),
+                segment => {
+                    return Err(crate::Error::UnsupportedSegment {
+                        segment: segment.clone(),
+                        format: "JSONPath",
+                    })
+                }
+            }
+        }
+        Ok(path)
+    }
+}
+
+/// Writes the name selector with the escapes of a normalized path.
+fn write_name(path: &mut String, name: &str) {
+    path.push_str("['");
+    for c in name.chars() {
+        match c {
+            '\'' => path.push_str("\\'"),
+            '\\' => path.push_str("\\\\"),
+            '\u{8}' => path.push_str("\\b"),
+            '\u{c}' => path.push_str("\\f"),
+            '\n' => path.push_str("\\n"),
+            '\r' => path.push_str("\\r"),
+            '\t' => path.push_str("\\t"),
+            c if c < '\u{20}' => write!(path, "\\u{:04x}", c as u32).unwrap(),
+            c => path.push(c),
+        }
+    }
+    path.push_str("']");
+}
+
+/// Creates a field from a member name, which is already unescaped.
+pub(crate) fn name(name: Cow<'_, str>) -> Field<'_> {
+    Field {
+        requires_quoting: !is_valid_field_name(&name),
+        name,
+    }
+}
+
+/// Resolves the escapes of the content of a string literal enclosed in
+/// `quote`, which is the only quote that may be escaped.
+pub(crate) fn unescape(s: &str, quote: char) -> Result<Cow<'_, str>, &'static str> {
+    if s.chars().any(|c| c < '\u{20}') {
+        return Err("control characters must be escaped in a string literal");
+    }
+    if !s.contains('\\') {
+        return Ok(Cow::Borrowed(s));
+    }
+    let mut ret = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            ret.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('b') => ret.push('\u{8}'),
+            Some('f') => ret.push('\u{c}'),
+            Some('n') => ret.push('\n'),
+            Some('r') => ret.push('\r'),
+            Some('t') => ret.push('\t'),
+            Some(c @ ('/' | '\\')) => ret.push(c),
+            Some(c) if c == quote => ret.push(c),
+            Some('u') => {
+                let high = hex4(&mut chars)?;
+                let code = if (0xD800..0xDC00).contains(&high) {
+                    // A high surrogate must be followed by a low one.
+                    if chars.next() != Some('\\') || chars.next() != Some('u') {
+                        return Err("unpaired surrogate in a string literal");
+                    }
+                    let low = hex4(&mut chars)?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err("unpaired surrogate in a string literal");
+                    }
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    high
+                };
+                ret.push(char::from_u32(code).ok_or("unpaired surrogate in a string literal")?);
+            }
+            _ => return Err("invalid escape in a string literal"),
+        }
+    }
+    Ok(Cow::Owned(ret))
+}
+
+fn hex4(chars: &mut std::str::Chars<'_>) -> Result<u32, &'static str> {
+    let hex = chars.as_str().get(..4).ok_or("invalid unicode escape")?;
+    let code = u32::from_str_radix(hex, 16).map_err(|_| "invalid unicode escape")?;
+    *chars = chars.as_str()[4..].chars();
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use crate::{FieldBuf, SegmentBuf, Slice, ViewPath, ViewPathBuf};
+
+    #[test]
+    fn test_parse_json_path() {
+        let parse = |p| ViewPath::parse_json_path(p).unwrap();
+        let same = |jp, p| assert_eq!(ViewPath::parse_str(p).unwrap(), parse(jp), "{}", jp);
+        same("$", "$");
+        same("$.store.book[0].title", "store.book[0].title");
+        same("$['store'][\"book\"][-1]", "store.book[-1]");
+        same("$.store.*[*]", "store.*.*");
+        same("$..author", "..author");
+        same("$..[0]..*", "..[0]..*");
+        same("$[1:3]['a b'][::-1]", r#"[1:3]."a b"[::-1]"#);
+        same("$[ 1 : 3 ]", "[1:3]");
+        same("$.名前._1", "名前._1");
+        same(
+            r#"$['it\'s']["\"\\\/é🦀\n"]"#,
+            "\"it's\".\"\\\"\\\\/é🦀\\n\"",
+        );
+        same("$['']", r#""""#);
+
+        assert_eq!(
+            Slice::new(None, None, Some(2)),
+            match parse("$[::2]").pop_back() {
+                Some(crate::Segment::Slice(slice)) => slice,
+                other => panic!("unexpected {:?}", other),
+            }
+        );
+
+        for path in [
+            "a",
+            "$a",
+            "$.1a",
+            "$[01]",
+            "$[-0]",
+            "$['a', 'b']",
+            "$[?@.a]",
+            r#"$['\"']"#,
+            r#"$["\'"]"#,
+            r#"$['\q']"#,
+            r#"$['\ud83e']"#,
+            "$['\n']",
+            "$[99999999999999999999]",
+            "$..",
+        ] {
+            assert!(ViewPath::parse_json_path(path).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_to_json_path() {
+        let json_path = |p| ViewPathBuf::parse_str(p).unwrap().to_json_path();
+        assert_eq!("$", json_path("$").unwrap());
+        assert_eq!("$['a'][0]['b c']", json_path(r#"a[0]."b c""#).unwrap());
+        assert_eq!(
+            r#"$['it\'s']['\\\n\u0001\u001f\b']"#,
+            json_path(r#""it's"."\\\n\u{1}\u{1f}\u{8}""#).unwrap()
+        );
+        assert_eq!(
+            "$[*][1:-1]..['id']..[*]",
+            json_path("*[1:-1]..id..*").unwrap()
+        );
+        for path in ["(a | b)", "a[?(@.b)]"] {
+            assert!(json_path(path).is_err(), "{}", path);
+        }
+
+        for path in ["$", "$['a']['\\'\\n'][-2]..[0][::2]", "$..['名']"] {
+            let buf = ViewPathBuf::parse_json_path(path).unwrap();
+            assert_eq!(path, buf.to_json_path().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_json_path_quickcheck() {
+        fn test_json_path(fields: Vec<FieldBuf>) -> quickcheck::TestResult {
+            let view_path = ViewPathBuf::from(
+                fields
+                    .into_iter()
+                    .map(SegmentBuf::Field)
+                    .collect::<VecDeque<_>>(),
+            );
+            let parsed = ViewPathBuf::parse_json_path(&view_path.to_json_path().unwrap()).unwrap();
+            assert_eq!(view_path, parsed);
+            quickcheck::TestResult::passed()
+        }
+        quickcheck::QuickCheck::new()
+            .tests(500)
+            .max_tests(1_000)
+            .quickcheck(test_json_path as fn(Vec<FieldBuf>) -> quickcheck::TestResult)
+    }
+}
@@ -1,19 +1,39 @@
 #![feature(once_cell)]
 
+mod compiled;
 mod field;
+mod filter;
+mod json_path;
+mod navigable;
+mod pointer;
 mod segment;
+mod slice;
 mod view_path;
 
+pub use compiled::{field_hash, CompiledPath, CompiledSegment, PathCache, SegmentRef};
 pub use field::{Field, FieldBuf};
+pub use filter::{CompareOp, Filter, Literal, Operand, Pattern};
+pub use navigable::{Leaf, Navigable};
 pub use segment::{Segment, SegmentBuf};
+pub use slice::Slice;
 pub use view_path::{ViewPath, ViewPathBuf};
 
 pub mod parser;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("invalid path {0}")]
-    InvalidPath(String),
+    #[error("invalid path: {0}")]
+    InvalidPath(parser::ParseError),
+    #[error("invalid json pointer `{pointer}`: {reason}")]
+    InvalidPointer {
+        pointer: String,
+        reason: &'static str,
+    },
+    #[error("`{segment}` cannot be written as a {format}")]
+    UnsupportedSegment {
+        segment: SegmentBuf,
+        format: &'static str,
+    },
 }
 
 #[cfg(test)]
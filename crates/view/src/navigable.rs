@@ -0,0 +1,433 @@
+//! The document model the paths are evaluated against.
+
+use std::{borrow::Cow, fmt};
+
+use crate::SegmentBuf;
+
+/// Leaf is the scalar held by a node that has no children.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Leaf<'a> {
+    Null,
+    Bool(bool),
+    /// The number as written, so no precision is lost.
+    Number(Cow<'a, str>),
+    String(Cow<'a, str>),
+}
+
+impl<'a> Leaf<'a> {
+    /// Returns the number as a float, if the leaf is a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Leaf::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Leaf<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Leaf::Null => f.write_str("null"),
+            Leaf::Bool(b) => write!(f, "{}", b),
+            Leaf::Number(n) => f.write_str(n),
+            Leaf::String(s) => f.write_str(s),
+        }
+    }
+}
+
+/// Navigable is a tree of maps, arrays and leaves that a `ViewPath` can
+/// walk, like a JSON, TOML or YAML document.
+///
+/// Fields look up the children of maps and indexes the children of arrays.
+#[allow(clippy::len_without_is_empty)]
+pub trait Navigable {
+    /// Returns the child of a map by its key.
+    fn get_field(&self, name: &str) -> Option<&Self>;
+
+    /// Returns the child of a map by its key, whose `field_hash` was
+    /// precomputed by a compiled path. The maps keyed by that hash can skip
+    /// hashing the key, the others look it up as `get_field` does.
+    fn get_field_hashed(&self, name: &str, hash: u64) -> Option<&Self> {
+        let _ = hash;
+        self.get_field(name)
+    }
+
+    /// Returns the child of an array by its position.
+    fn get_index(&self, index: usize) -> Option<&Self>;
+
+    /// Returns the number of children of an array or a map, or `None` for
+    /// a leaf.
+    fn len(&self) -> Option<usize>;
+
+    /// Returns the children of an array or a map in document order, along
+    /// with the segment leading to each of them.
+    fn children(&self) -> Box<dyn Iterator<Item = (SegmentBuf, &Self)> + '_>;
+
+    /// Returns the scalar of a leaf, or `None` for an array or a map.
+    fn as_leaf(&self) -> Option<Leaf<'_>>;
+
+    fn is_array(&self) -> bool;
+
+    fn is_map(&self) -> bool;
+
+    /// Returns the name of the type of the node, for error messages.
+    fn type_name(&self) -> &'static str;
+
+    /// Returns the name of the maps, which fields expect, for error messages.
+    fn map_type_name() -> &'static str
+    where
+        Self: Sized,
+    {
+        "object"
+    }
+
+    /// Returns the name of the arrays, which indexes expect, for error
+    /// messages.
+    fn array_type_name() -> &'static str
+    where
+        Self: Sized,
+    {
+        "array"
+    }
+
+    /// Returns the text of a boolean, number or string leaf. Nulls, arrays
+    /// and maps have none.
+    fn leaf_to_string(&self) -> Option<Cow<'_, str>> {
+        match self.as_leaf()? {
+            Leaf::Null => None,
+            Leaf::String(s) => Some(s),
+            leaf => Some(Cow::Owned(leaf.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl Navigable for serde_json::Value {
+    fn get_field(&self, name: &str) -> Option<&Self> {
+        self.as_object()?.get(name)
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Self> {
+        self.as_array()?.get(index)
+    }
+
+    fn len(&self) -> Option<usize> {
+        match self {
+            serde_json::Value::Array(a) => Some(a.len()),
+            serde_json::Value::Object(m) => Some(m.len()),
+            _ => None,
+        }
+    }
+
+    fn children(&self) -> Box<dyn Iterator<Item = (SegmentBuf, &Self)> + '_> {
+        match self {
+            serde_json::Value::Array(a) => Box::new(
+                a.iter()
+                    .enumerate()
+                    .map(|(i, v)| (SegmentBuf::index(i as isize), v)),
+            ),
+            serde_json::Value::Object(m) => Box::new(
+                m.iter()
+                    .map(|(k, v)| (SegmentBuf::field(crate::FieldBuf::new(k.as_str())), v)),
+            ),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn as_leaf(&self) -> Option<Leaf<'_>> {
+        match self {
+            serde_json::Value::Null => Some(Leaf::Null),
+            serde_json::Value::Bool(b) => Some(Leaf::Bool(*b)),
+            serde_json::Value::Number(n) => Some(Leaf::Number(Cow::Owned(n.to_string()))),
+            serde_json::Value::String(s) => Some(Leaf::String(Cow::Borrowed(s))),
+            _ => None,
+        }
+    }
+
+    fn is_array(&self) -> bool {
+        self.is_array()
+    }
+
+    fn is_map(&self) -> bool {
+        self.is_object()
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            serde_json::Value::Null => "null",
+            serde_json::Value::Bool(_) => "boolean",
+            serde_json::Value::Number(_) => "number",
+            serde_json::Value::String(_) => "string",
+            serde_json::Value::Array(_) => "array",
+            serde_json::Value::Object(_) => "object",
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl Navigable for toml::Value {
+    fn get_field(&self, name: &str) -> Option<&Self> {
+        self.as_table()?.get(name)
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Self> {
+        self.as_array()?.get(index)
+    }
+
+    fn len(&self) -> Option<usize> {
+        match self {
+            toml::Value::Array(a) => Some(a.len()),
+            toml::Value::Table(t) => Some(t.len()),
+            _ => None,
+        }
+    }
+
+    fn children(&self) -> Box<dyn Iterator<Item = (SegmentBuf, &Self)> + '_> {
+        match self {
+            toml::Value::Array(a) => Box::new(
+                a.iter()
+                    .enumerate()
+                    .map(|(i, v)| (SegmentBuf::index(i as isize), v)),
+            ),
+            toml::Value::Table(t) => Box::new(
+                t.iter()
+                    .map(|(k, v)| (SegmentBuf::field(crate::FieldBuf::new(k.as_str())), v)),
+            ),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    // TOML has no null, and datetimes are compared as their text. Floats are
+    // written as TOML writes them, so `1.0` keeps its fraction.
+    fn as_leaf(&self) -> Option<Leaf<'_>> {
+        match self {
+            toml::Value::Boolean(b) => Some(Leaf::Bool(*b)),
+            toml::Value::Integer(i) => Some(Leaf::Number(Cow::Owned(i.to_string()))),
+            toml::Value::Float(_) => Some(Leaf::Number(Cow::Owned(self.to_string()))),
+            toml::Value::String(s) => Some(Leaf::String(Cow::Borrowed(s))),
+            toml::Value::Datetime(d) => Some(Leaf::String(Cow::Owned(d.to_string()))),
+            _ => None,
+        }
+    }
+
+    fn is_array(&self) -> bool {
+        self.is_array()
+    }
+
+    fn is_map(&self) -> bool {
+        self.is_table()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.type_str()
+    }
+
+    fn map_type_name() -> &'static str {
+        "table"
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl Navigable for serde_yaml::Value {
+    // Keys that are not strings are looked up by their text, like `1`,
+    // `true` or `null`.
+    fn get_field(&self, name: &str) -> Option<&Self> {
+        let m = self.as_mapping()?;
+        m.get(&serde_yaml::Value::String(name.to_string()))
+            .or_else(|| {
+                m.iter()
+                    .find(|(k, _)| !k.is_string() && yaml_key(k).as_deref() == Some(name))
+                    .map(|(_, v)| v)
+            })
+    }
+
+    fn get_index(&self, index: usize) -> Option<&Self> {
+        self.as_sequence()?.get(index)
+    }
+
+    fn len(&self) -> Option<usize> {
+        match self {
+            serde_yaml::Value::Sequence(s) => Some(s.len()),
+            serde_yaml::Value::Mapping(m) => Some(m.len()),
+            _ => None,
+        }
+    }
+
+    // The entries whose keys are sequences or mappings cannot be addressed
+    // by a field, so they are skipped.
+    fn children(&self) -> Box<dyn Iterator<Item = (SegmentBuf, &Self)> + '_> {
+        match self {
+            serde_yaml::Value::Sequence(s) => Box::new(
+                s.iter()
+                    .enumerate()
+                    .map(|(i, v)| (SegmentBuf::index(i as isize), v)),
+            ),
+            serde_yaml::Value::Mapping(m) => Box::new(m.iter().filter_map(|(k, v)| {
+                Some((SegmentBuf::field(crate::FieldBuf::new(yaml_key(k)?)), v))
+            })),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn as_leaf(&self) -> Option<Leaf<'_>> {
+        match self {
+            serde_yaml::Value::Null => Some(Leaf::Null),
+            serde_yaml::Value::Bool(b) => Some(Leaf::Bool(*b)),
+            serde_yaml::Value::Number(n) => Some(Leaf::Number(Cow::Owned(n.to_string()))),
+            serde_yaml::Value::String(s) => Some(Leaf::String(Cow::Borrowed(s))),
+            _ => None,
+        }
+    }
+
+    fn is_array(&self) -> bool {
+        self.is_sequence()
+    }
+
+    fn is_map(&self) -> bool {
+        self.is_mapping()
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            serde_yaml::Value::Null => "null",
+            serde_yaml::Value::Bool(_) => "boolean",
+            serde_yaml::Value::Number(_) => "number",
+            serde_yaml::Value::String(_) => "string",
+            serde_yaml::Value::Sequence(_) => "sequence",
+            serde_yaml::Value::Mapping(_) => "mapping",
+        }
+    }
+
+    fn map_type_name() -> &'static str {
+        "mapping"
+    }
+
+    fn array_type_name() -> &'static str {
+        "sequence"
+    }
+}
+
+/// Returns the text a yaml key is addressed by, or `None` for sequences and
+/// mappings.
+#[cfg(feature = "yaml")]
+fn yaml_key(key: &serde_yaml::Value) -> Option<Cow<'_, str>> {
+    match key {
+        serde_yaml::Value::Null => Some(Cow::Borrowed("null")),
+        key => key.leaf_to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use serde_json::json;
+
+    use crate::navigable::{Leaf, Navigable};
+
+    #[test]
+    fn test_navigable_json() {
+        let value = json!({"a": [1, 2.5, "s", null, true], "b": {}});
+        let a = value.get_field("a").unwrap();
+        assert_eq!(Some(5), a.len());
+        assert_eq!(Some(2), value.len());
+        assert_eq!(None, a.get_field("a"));
+        assert_eq!(Some(&json!("s")), a.get_index(2));
+        assert_eq!(None, a.get_index(5));
+        assert_eq!(
+            vec!["a", "b"],
+            value
+                .children()
+                .map(|(s, _)| s.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Some("1"), Some("2.5"), Some("s"), None, Some("true")],
+            a.children()
+                .map(|(_, v)| v.leaf_to_string())
+                .collect::<Vec<_>>()
+                .iter()
+                .map(|s| s.as_deref())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(2.5),
+            a.get_index(1).unwrap().as_leaf().unwrap().as_f64()
+        );
+        assert_eq!(Some(Leaf::Null), a.get_index(3).unwrap().as_leaf());
+        assert_eq!(None, value.as_leaf());
+        assert!(a.is_array() && !a.is_map());
+        assert_eq!("object", Navigable::type_name(&value));
+    }
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod toml_tests {
+    use crate::navigable::{Leaf, Navigable};
+
+    #[test]
+    fn test_navigable_toml() {
+        let value: toml::Value = toml::from_str(
+            "a = [1, 2.5, 'x', 1.0]\nb = 1979-05-27T07:32:00Z\nc = { d = true }\n[[e]]\nf = 1",
+        )
+        .unwrap();
+        let a = value.get_field("a").unwrap();
+        assert_eq!(Some(4), a.len());
+        assert_eq!(Some(4), value.len());
+        assert_eq!(
+            vec![Some("1"), Some("2.5"), Some("x"), Some("1.0")],
+            a.children()
+                .map(|(_, v)| v.leaf_to_string().map(|s| s.into_owned()))
+                .collect::<Vec<_>>()
+                .iter()
+                .map(|s| s.as_deref())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(Leaf::String("1979-05-27T07:32:00Z".into())),
+            value.get_field("b").unwrap().as_leaf()
+        );
+        let c = value.get_field("c").unwrap();
+        assert!(c.is_map());
+        assert_eq!(Some(Leaf::Bool(true)), c.get_field("d").unwrap().as_leaf());
+        let e = value.get_field("e").unwrap();
+        assert!(e.is_array());
+        assert_eq!("table", Navigable::type_name(e.get_index(0).unwrap()));
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod yaml_tests {
+    use crate::navigable::{Leaf, Navigable};
+
+    #[test]
+    fn test_navigable_yaml() {
+        let value: serde_yaml::Value =
+            serde_yaml::from_str("a: [1, 2.5, x, ~]\n1: one\ntrue: yes\n[k]: v\n").unwrap();
+        let a = value.get_field("a").unwrap();
+        assert_eq!(Some(4), a.len());
+        assert_eq!(Some(Leaf::Null), a.get_index(3).unwrap().as_leaf());
+        assert_eq!(
+            Some(2.5),
+            a.get_index(1).unwrap().as_leaf().unwrap().as_f64()
+        );
+        assert_eq!(Some(&serde_yaml::Value::from("one")), value.get_field("1"));
+        assert_eq!(
+            vec!["a", r#""1""#, "true"],
+            value
+                .children()
+                .map(|(s, _)| s.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(4), value.len());
+        assert_eq!("sequence", Navigable::type_name(a));
+    }
+
+    #[test]
+    fn test_navigable_yaml_null_key() {
+        let value: serde_yaml::Value = serde_yaml::from_str("~: 1\n").unwrap();
+        let children = value.children().collect::<Vec<_>>();
+        assert_eq!(1, children.len());
+        assert_eq!("null", children[0].0.to_string());
+        assert_eq!(Some(children[0].1), value.get_field("null"));
+        assert_eq!(Some(&serde_yaml::Value::from(1)), value.get_field("null"));
+    }
+}
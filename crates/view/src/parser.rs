@@ -1,3 +1,5 @@
+use std::{fmt, ops::Range};
+
 use lalrpop_util::lalrpop_mod;
 
 use crate::ViewPath;
@@ -8,9 +10,223 @@ lalrpop_mod!(
     path
 );
 
+lalrpop_mod!(
+    #[allow(clippy::all)]
+    #[allow(unused)]
+    jsonpath
+);
+
+thread_local! {
+    // Building the parser compiles its lexer, so it is done once.
+    static VIEW_PATH_PARSER: path::ViewPathParser = path::ViewPathParser::new();
+    static JSON_PATH_PARSER: jsonpath::JsonPathParser = jsonpath::JsonPathParser::new();
+}
+
 /// Parses the string as a lookup path.
-pub fn parse_view_path(s: &str) -> Result<ViewPath, String> {
-    path::ViewPathParser::new()
-        .parse(s)
-        .map_err(|err| format!("{}", err))
+pub fn parse_view_path(s: &str) -> Result<ViewPath<'_>, ParseError> {
+    VIEW_PATH_PARSER
+        .with(|parser| parser.parse(s))
+        .map_err(|err| ParseError::new(s, err))
+}
+
+/// Parses the string as a JSONPath (RFC 9535).
+///
+/// Only the selectors that have a segment are accepted: names, indexes,
+/// wildcards, slices and descendants, one selector per bracket.
+pub fn parse_json_path(s: &str) -> Result<ViewPath<'_>, ParseError> {
+    JSON_PATH_PARSER
+        .with(|parser| parser.parse(s))
+        .map_err(|err| ParseError::new(s, err))
+}
+
+/// The error of a grammar action, which rejects the token at `span`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UserError {
+    span: Range<usize>,
+    message: String,
+}
+
+/// Rejects the token between `l` and `r` from a grammar action.
+pub(crate) fn reject<T>(
+    l: usize,
+    r: usize,
+    message: impl Into<String>,
+) -> lalrpop_util::ParseError<usize, T, UserError> {
+    lalrpop_util::ParseError::User {
+        error: UserError {
+            span: l..r,
+            message: message.into(),
+        },
+    }
+}
+
+/// Returns the reason of a regex syntax error, which is the last line of its
+/// text as the pattern and its own marker are printed by `ParseError`.
+pub(crate) fn regex_error(err: &regex::Error) -> String {
+    let text = err.to_string();
+    let last = text.lines().last().unwrap_or_default();
+    last.strip_prefix("error: ").unwrap_or(last).to_string()
+}
+
+/// ParseError tells where and why a path failed to parse.
+///
+/// Its `Display` prints the path with a `^` marker under the offending
+/// token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The path that failed to parse.
+    pub input: String,
+    /// The byte range of the offending token in `input`.
+    pub span: Range<usize>,
+    /// The tokens that would have been accepted at `span`, by their names.
+    pub expected: Vec<String>,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new<T: fmt::Display>(
+        input: &str,
+        err: lalrpop_util::ParseError<usize, T, UserError>,
+    ) -> Self {
+        use lalrpop_util::ParseError::*;
+
+        let (span, expected, message) = match err {
+            InvalidToken { location } => {
+                let len = input[location..].chars().next().map_or(0, char::len_utf8);
+                (
+                    location..location + len,
+                    vec![],
+                    "invalid token".to_string(),
+                )
+            }
+            UnrecognizedEOF { location, expected } => (
+                location..location,
+                describe(&expected),
+                "unexpected end of path".to_string(),
+            ),
+            UnrecognizedToken {
+                token: (l, token, r),
+                expected,
+            } => (
+                l..r,
+                describe(&expected),
+                format!("unrecognized token `{}`", token),
+            ),
+            ExtraToken {
+                token: (l, token, r),
+            } => (l..r, vec![], format!("extra token `{}`", token)),
+            User { error } => (error.span, vec![], error.message),
+        };
+        Self {
+            input: input.to_string(),
+            span,
+            expected,
+            message,
+        }
+    }
+}
+
+/// Names the terminals of the grammars as users know them. The literal
+/// tokens are quoted in backticks, the tokens matched by a regex are named
+/// after what they match.
+fn describe(terminals: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(terminals.len());
+    for terminal in terminals {
+        let name = match terminal.as_str() {
+            r###"r#"[0-9]*[\\p{XID_Start}_]\\p{XID_Continue}*"#"### => "field name".to_string(),
+            r###"r#"[A-Za-z_\\u{80}-\\u{10FFFF}][A-Za-z0-9_\\u{80}-\\u{10FFFF}]*"#"### => {
+                "member name".to_string()
+            }
+            r###"r#"\"(\\\\.|[^\"\\\\])*\""#"### | r###"r#"'(\\\\.|[^'\\\\])*'"#"### => {
+                "quoted string".to_string()
+            }
+            r###"r#"-?[0-9]+"#"### | r###"r#"0|-?[1-9][0-9]*"#"### => "integer".to_string(),
+            r###"r#"-?[0-9]+\\.[0-9]+([eE][+-]?[0-9]+)?"#"### => "number".to_string(),
+            t => match t.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+                Some(token) => format!("`{}`", token),
+                None => t.to_string(),
+            },
+        };
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Columns are counted in chars so the marker lines up in a terminal.
+        let column = self.input[..self.span.start].chars().count();
+        let width = self.input[self.span.clone()].chars().count().max(1);
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "  {}", self.input)?;
+        write!(f, "  {}{}", " ".repeat(column), "^".repeat(width))?;
+        if !self.expected.is_empty() {
+            write!(f, "\nexpected one of {}", self.expected.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{parse_json_path, parse_view_path};
+
+    #[test]
+    fn test_parse_error_span() {
+        let err = parse_view_path("a.b[x]").unwrap_err();
+        assert_eq!(4..5, err.span);
+        assert_eq!("a.b[x]", err.input);
+        assert_eq!(
+            "unrecognized token `x`\n  a.b[x]\n      ^\nexpected one of `*`, `:`, `?`, integer",
+            err.to_string()
+        );
+
+        let err = parse_view_path("a.b[").unwrap_err();
+        assert_eq!(4..4, err.span);
+        assert!(err
+            .to_string()
+            .starts_with("unexpected end of path\n  a.b[\n      ^\n"));
+
+        let err = parse_view_path("名前.%").unwrap_err();
+        assert_eq!(7..8, err.span);
+        assert_eq!("invalid token\n  名前.%\n     ^", err.to_string());
+
+        let err = parse_view_path("a[99999999999999999999].b").unwrap_err();
+        assert_eq!(2..22, err.span);
+        assert!(err.to_string().starts_with("the index is too large\n"));
+
+        let err = parse_view_path(r#"a[?(@.b =~ "(")]"#).unwrap_err();
+        assert_eq!(11..14, err.span);
+        assert_eq!(
+            "the regex is invalid: unclosed group\n  a[?(@.b =~ \"(\")]\n             ^^^",
+            err.to_string()
+        );
+
+        let err = parse_view_path("a[?(@.b ==").unwrap_err();
+        assert_eq!(
+            vec![
+                "`@`",
+                "`false`",
+                "`null`",
+                "`true`",
+                "quoted string",
+                "integer",
+                "number"
+            ],
+            err.expected
+        );
+
+        let err = parse_json_path("$[").unwrap_err();
+        assert_eq!(
+            "unexpected end of path\n  $[\n    ^\nexpected one of `*`, `:`, quoted string, integer",
+            err.to_string()
+        );
+
+        let err = parse_json_path("$['a\\x']").unwrap_err();
+        assert_eq!(2..7, err.span);
+    }
 }
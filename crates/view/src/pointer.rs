@@ -0,0 +1,128 @@
+//! Conversions between `ViewPathBuf` and JSON Pointer (RFC 6901).
+
+use crate::{FieldBuf, SegmentBuf, ViewPathBuf};
+
+impl ViewPathBuf {
+    /// Renders the path as a JSON Pointer, escaping `~` as `~0` and `/` as
+    /// `~1` in the field names.
+    ///
+    /// A pointer addresses exactly one location and cannot count from the
+    /// end of an array, so only fields and non-negative indexes convert. A
+    /// coalesce segment depends on which of its fields the document has, so
+    /// it is rejected like wildcards, descendants, slices and filters: resolve
+    /// the path against the document first to get a concrete one.
+    pub fn to_json_pointer(&self) -> Result<String, crate::Error> {
+        let mut pointer = String::new();
+        for segment in self.iter() {
+            pointer.push('/');
+            match segment {
+                SegmentBuf::Field(field) => {
+                    pointer.push_str(&field.as_str().replace('~', "~0").replace('/', "~1"))
+                }
+                SegmentBuf::Index(i) if *i >= 0 => pointer.push_str(&i.to_string()),
+                segment => {
+                    return Err(crate::Error::UnsupportedSegment {
+                        segment: segment.clone(),
+                        format: "json pointer",
+                    })
+                }
+            }
+        }
+        Ok(pointer)
+    }
+
+    /// Parses a JSON Pointer.
+    ///
+    /// A pointer does not tell arrays from objects, so the reference tokens
+    /// made of digits, without leading zeros, become indexes and the others
+    /// become fields. An index cannot address the key of an object, like
+    /// `"1"`: resolve the path against the document with
+    /// `ejson::resolve_pointer` to turn such indexes into fields.
+    pub fn from_json_pointer(pointer: &str) -> Result<Self, crate::Error> {
+        let invalid = |reason| crate::Error::InvalidPointer {
+            pointer: pointer.to_string(),
+            reason,
+        };
+        let mut path = ViewPathBuf::root();
+        if pointer.is_empty() {
+            return Ok(path);
+        }
+        let tokens = pointer
+            .strip_prefix('/')
+            .ok_or_else(|| invalid("it must start with `/`"))?;
+        for token in tokens.split('/') {
+            let is_index = token == "0"
+                || (!token.starts_with('0') && token.bytes().all(|b| b.is_ascii_digit()));
+            if !token.is_empty() && is_index {
+                let i = token
+                    .parse::<isize>()
+                    .map_err(|_| invalid("the index is too large"))?;
+                path.push_back(SegmentBuf::index(i));
+            } else {
+                path.push_back(SegmentBuf::field(FieldBuf::new(
+                    unescape(token).ok_or_else(|| invalid("`~` must be followed by `0` or `1`"))?,
+                )));
+            }
+        }
+        Ok(path)
+    }
+}
+
+fn unescape(token: &str) -> Option<String> {
+    let mut ret = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '~' => match chars.next() {
+                Some('0') => ret.push('~'),
+                Some('1') => ret.push('/'),
+                _ => return None,
+            },
+            c => ret.push(c),
+        }
+    }
+    Some(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FieldBuf, SegmentBuf, ViewPathBuf};
+
+    #[test]
+    fn test_to_json_pointer() {
+        let pointer = |p| ViewPathBuf::parse_str(p).unwrap().to_json_pointer();
+        assert_eq!("", pointer("$").unwrap());
+        assert_eq!("/a/0/b", pointer("a[0].b").unwrap());
+        assert_eq!("/a~1b/c~0d/", pointer(r#""a/b"."c~d"."""#).unwrap());
+        assert_eq!("/[0]/\"", pointer(r#""[0]"."\"""#).unwrap());
+        for path in ["a[-1]", "(a | b)", "a.*", "..a", "a[1:]", "a[?(@.b)]"] {
+            assert!(pointer(path).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_from_json_pointer() {
+        let path = |p| ViewPathBuf::from_json_pointer(p).unwrap();
+        assert_eq!(ViewPathBuf::root(), path(""));
+        assert_eq!(ViewPathBuf::parse_str("a[0].b").unwrap(), path("/a/0/b"));
+        assert_eq!(
+            ViewPathBuf::parse_str(r#""a/b"."c~d".""."01""#).unwrap(),
+            path("/a~1b/c~0d//01")
+        );
+        assert_eq!(
+            ViewPathBuf::from(SegmentBuf::field(FieldBuf::new("~1"))),
+            path("/~01")
+        );
+        for pointer in ["a", "/a~", "/a~2", "/99999999999999999999999"] {
+            assert!(
+                ViewPathBuf::from_json_pointer(pointer).is_err(),
+                "{}",
+                pointer
+            );
+        }
+
+        for pointer in ["", "/a/0/b~1c", "/~0/", "/x/10/-"] {
+            assert_eq!(pointer, path(pointer).to_json_pointer().unwrap());
+        }
+    }
+}
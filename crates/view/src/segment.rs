@@ -1,6 +1,6 @@
 use std::fmt;
 
-use crate::{Field, FieldBuf};
+use crate::{Field, FieldBuf, Filter, Slice};
 
 /// Segment is a chunk of a `ViewPath`.
 ///
@@ -10,6 +10,12 @@ pub enum Segment<'a> {
     Field(Field<'a>),
     Index(isize),
     Coalesce(Vec<Field<'a>>),
+    Wildcard,
+    /// Selects the current value and all of its descendants, the following
+    /// segment is then applied to each of them.
+    Descendant,
+    Slice(Slice),
+    Filter(Box<Filter>),
 }
 
 impl<'a> Segment<'a> {
@@ -37,6 +43,61 @@ impl<'a> Segment<'a> {
         matches!(self, Segment::Coalesce(_))
     }
 
+    pub fn wildcard() -> Self {
+        Self::Wildcard
+    }
+
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self, Segment::Wildcard)
+    }
+
+    pub fn descendant() -> Self {
+        Self::Descendant
+    }
+
+    pub fn is_descendant(&self) -> bool {
+        matches!(self, Segment::Descendant)
+    }
+
+    pub fn slice(slice: Slice) -> Self {
+        Self::Slice(slice)
+    }
+
+    pub fn is_slice(&self) -> bool {
+        matches!(self, Segment::Slice(_))
+    }
+
+    pub fn filter(filter: Filter) -> Self {
+        Self::Filter(Box::new(filter))
+    }
+
+    pub fn is_filter(&self) -> bool {
+        matches!(self, Segment::Filter(_))
+    }
+
+    /// Returns true if the segment selects at most one child.
+    pub fn is_singular(&self) -> bool {
+        matches!(
+            self,
+            Segment::Field(_) | Segment::Index(_) | Segment::Coalesce(_)
+        )
+    }
+
+    /// Detaches the segment from the string it borrows from.
+    pub fn into_owned(self) -> Segment<'static> {
+        match self {
+            Segment::Field(field) => Segment::Field(field.into_owned()),
+            Segment::Index(i) => Segment::Index(i),
+            Segment::Coalesce(v) => {
+                Segment::Coalesce(v.into_iter().map(Field::into_owned).collect())
+            }
+            Segment::Wildcard => Segment::Wildcard,
+            Segment::Descendant => Segment::Descendant,
+            Segment::Slice(slice) => Segment::Slice(slice),
+            Segment::Filter(filter) => Segment::Filter(filter),
+        }
+    }
+
     pub fn to_segment_buf(&self) -> SegmentBuf {
         match self {
             Segment::Field(field) => SegmentBuf::field(field.to_field_buf()),
@@ -44,6 +105,10 @@ impl<'a> Segment<'a> {
             Segment::Coalesce(v) => {
                 SegmentBuf::coalesce(v.iter().map(|field| field.to_field_buf()).collect())
             }
+            Segment::Wildcard => SegmentBuf::wildcard(),
+            Segment::Descendant => SegmentBuf::descendant(),
+            Segment::Slice(slice) => SegmentBuf::slice(*slice),
+            Segment::Filter(filter) => SegmentBuf::Filter(filter.clone()),
         }
     }
 }
@@ -61,6 +126,10 @@ impl<'a> fmt::Display for Segment<'a> {
                     .collect::<Vec<_>>()
                     .join(" | ")
             ),
+            Segment::Wildcard => write!(f, "*"),
+            Segment::Descendant => write!(f, ".."),
+            Segment::Slice(slice) => write!(f, "{}", slice),
+            Segment::Filter(filter) => write!(f, "?({})", filter),
         }
     }
 }
@@ -97,6 +166,12 @@ pub enum SegmentBuf {
     Field(FieldBuf),
     Index(isize),
     Coalesce(Vec<FieldBuf>),
+    Wildcard,
+    /// Selects the current value and all of its descendants, the following
+    /// segment is then applied to each of them.
+    Descendant,
+    Slice(Slice),
+    Filter(Box<Filter>),
 }
 
 impl SegmentBuf {
@@ -124,6 +199,46 @@ impl SegmentBuf {
         matches!(self, SegmentBuf::Coalesce(_))
     }
 
+    pub fn wildcard() -> Self {
+        Self::Wildcard
+    }
+
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self, SegmentBuf::Wildcard)
+    }
+
+    pub fn descendant() -> Self {
+        Self::Descendant
+    }
+
+    pub fn is_descendant(&self) -> bool {
+        matches!(self, SegmentBuf::Descendant)
+    }
+
+    pub fn slice(slice: Slice) -> Self {
+        Self::Slice(slice)
+    }
+
+    pub fn is_slice(&self) -> bool {
+        matches!(self, SegmentBuf::Slice(_))
+    }
+
+    pub fn filter(filter: Filter) -> Self {
+        Self::Filter(Box::new(filter))
+    }
+
+    pub fn is_filter(&self) -> bool {
+        matches!(self, SegmentBuf::Filter(_))
+    }
+
+    /// Returns true if the segment selects at most one child.
+    pub fn is_singular(&self) -> bool {
+        matches!(
+            self,
+            SegmentBuf::Field(_) | SegmentBuf::Index(_) | SegmentBuf::Coalesce(_)
+        )
+    }
+
     pub fn as_segment(&self) -> Segment<'_> {
         match self {
             SegmentBuf::Field(field) => Segment::field(field.as_field()),
@@ -131,6 +246,10 @@ impl SegmentBuf {
             SegmentBuf::Coalesce(v) => {
                 Segment::coalesce(v.iter().map(|field| field.as_field()).collect())
             }
+            SegmentBuf::Wildcard => Segment::wildcard(),
+            SegmentBuf::Descendant => Segment::descendant(),
+            SegmentBuf::Slice(slice) => Segment::slice(*slice),
+            SegmentBuf::Filter(filter) => Segment::Filter(filter.clone()),
         }
     }
 }
@@ -148,6 +267,10 @@ impl fmt::Display for SegmentBuf {
                     .collect::<Vec<_>>()
                     .join(" | ")
             ),
+            SegmentBuf::Wildcard => write!(f, "*"),
+            SegmentBuf::Descendant => write!(f, ".."),
+            SegmentBuf::Slice(slice) => write!(f, "{}", slice),
+            SegmentBuf::Filter(filter) => write!(f, "?({})", filter),
         }
     }
 }
@@ -0,0 +1,102 @@
+use std::fmt;
+
+/// Slice is the `[start:end:step]` selector of an array.
+///
+/// Every bound may be omitted, and negative bounds count from the end of
+/// the array, following the Python semantics.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Slice {
+    pub start: Option<isize>,
+    pub end: Option<isize>,
+    pub step: Option<isize>,
+}
+
+impl Slice {
+    pub fn new(start: Option<isize>, end: Option<isize>, step: Option<isize>) -> Self {
+        Self { start, end, step }
+    }
+
+    /// Returns the indexes selected from an array of `len` elements, in
+    /// the order they are visited. A zero step selects nothing.
+    pub fn indices(&self, len: usize) -> Vec<usize> {
+        let len = len as isize;
+        let step = self.step.unwrap_or(1);
+        let normalize = |i: isize| if i < 0 { len + i } else { i };
+
+        let mut ret = Vec::new();
+        if step > 0 {
+            let lower = normalize(self.start.unwrap_or(0)).clamp(0, len);
+            let upper = normalize(self.end.unwrap_or(len)).clamp(0, len);
+            let mut i = lower;
+            while i < upper {
+                ret.push(i as usize);
+                match i.checked_add(step) {
+                    Some(next) => i = next,
+                    None => break,
+                }
+            }
+        } else if step < 0 {
+            let upper = self
+                .start
+                .map(normalize)
+                .unwrap_or(len - 1)
+                .clamp(-1, len - 1);
+            let lower = self.end.map(normalize).unwrap_or(-1).clamp(-1, len - 1);
+            let mut i = upper;
+            while lower < i {
+                ret.push(i as usize);
+                match i.checked_add(step) {
+                    Some(next) => i = next,
+                    None => break,
+                }
+            }
+        }
+        ret
+    }
+}
+
+impl fmt::Display for Slice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = self.start {
+            write!(f, "{}", start)?;
+        }
+        f.write_str(":")?;
+        if let Some(end) = self.end {
+            write!(f, "{}", end)?;
+        }
+        if let Some(step) = self.step {
+            write!(f, ":{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Slice;
+
+    #[test]
+    fn test_slice_indices() {
+        let slice = |start, end, step| Slice::new(start, end, step).indices(5);
+        assert_eq!(vec![0, 1, 2, 3, 4], slice(None, None, None));
+        assert_eq!(vec![0, 1], slice(None, Some(2), None));
+        assert_eq!(vec![3, 4], slice(Some(-2), None, None));
+        assert_eq!(vec![0, 2, 4], slice(None, None, Some(2)));
+        assert_eq!(vec![1, 3], slice(Some(1), Some(-1), Some(2)));
+        assert_eq!(vec![4, 3, 2, 1, 0], slice(None, None, Some(-1)));
+        assert_eq!(vec![3, 1], slice(Some(-2), Some(0), Some(-2)));
+        assert_eq!(vec![0, 1, 2, 3, 4], slice(Some(-10), Some(10), None));
+        assert!(slice(Some(3), Some(1), None).is_empty());
+        assert!(slice(None, None, Some(0)).is_empty());
+        assert!(Slice::default().indices(0).is_empty());
+        assert_eq!(vec![1], slice(Some(1), None, Some(isize::MAX)));
+        assert_eq!(vec![4], slice(None, None, Some(isize::MIN)));
+    }
+
+    #[test]
+    fn test_slice_display() {
+        assert_eq!(":", Slice::default().to_string());
+        assert_eq!("1:-1", Slice::new(Some(1), Some(-1), None).to_string());
+        assert_eq!("::2", Slice::new(None, None, Some(2)).to_string());
+    }
+}
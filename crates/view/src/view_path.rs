@@ -19,11 +19,14 @@ pub struct ViewPath<'a> {
 
 impl<'a> fmt::Display for ViewPath<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return f.write_str("$");
+        }
         let mut peeker = self.segments.iter().peekable();
         while let Some(segment) = peeker.next() {
             let maybe_next = peeker
                 .peek()
-                .map(|next| next.is_field() || next.is_coalesce())
+                .map(|next| next.is_field() || next.is_coalesce() || next.is_wildcard())
                 .unwrap_or(false);
             match (segment, maybe_next) {
                 (Segment::Field(_), true) => write!(f, r#"{}."#, segment)?,
@@ -32,6 +35,13 @@ impl<'a> fmt::Display for ViewPath<'a> {
                 (Segment::Index(_), false) => write!(f, "[{}]", segment)?,
                 (Segment::Coalesce(_), true) => write!(f, r#"{}."#, segment)?,
                 (Segment::Coalesce(_), false) => write!(f, "{}", segment)?,
+                (Segment::Wildcard, true) => write!(f, r#"{}."#, segment)?,
+                (Segment::Wildcard, false) => write!(f, "{}", segment)?,
+                (Segment::Descendant, _) => write!(f, "{}", segment)?,
+                (Segment::Slice(_), true) => write!(f, r#"[{}]."#, segment)?,
+                (Segment::Slice(_), false) => write!(f, "[{}]", segment)?,
+                (Segment::Filter(_), true) => write!(f, r#"[{}]."#, segment)?,
+                (Segment::Filter(_), false) => write!(f, "[{}]", segment)?,
             }
         }
         Ok(())
@@ -81,8 +91,79 @@ impl<'a> ViewPath<'a> {
         crate::parser::parse_view_path(input).map_err(crate::Error::InvalidPath)
     }
 
+    /// Returns true if the path addresses at most one value, that is it
+    /// has no wildcard, descendant, slice or filter segments.
+    pub fn is_singular(&self) -> bool {
+        self.segments.iter().all(|s| s.is_singular())
+    }
+
+    /// Returns the number of segments, the root has none.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns true if the path has no segments, like `is_root`.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the path without its last segment, or `None` for the root.
+    pub fn parent(&self) -> Option<ViewPath<'a>> {
+        let mut parent = self.clone();
+        parent.pop_back().map(|_| parent)
+    }
+
+    /// Returns the last segment, or `None` for the root.
+    pub fn last(&self) -> Option<&Segment<'a>> {
+        self.segments.back()
+    }
+
+    /// Returns true if the path begins with all the segments of `needle`.
     pub fn starts_with(&self, needle: &ViewPath<'a>) -> bool {
-        needle.iter().zip(&self.segments).all(|(n, s)| n == s)
+        needle.len() <= self.len() && needle.iter().zip(&self.segments).all(|(n, s)| n == s)
+    }
+
+    /// Returns true if the path ends with all the segments of `needle`.
+    pub fn ends_with(&self, needle: &ViewPath<'a>) -> bool {
+        needle.len() <= self.len()
+            && needle
+                .iter()
+                .rev()
+                .zip(self.segments.iter().rev())
+                .all(|(n, s)| n == s)
+    }
+
+    /// Returns the rest of the path after `prefix`, or `None` if the path
+    /// does not start with it.
+    pub fn strip_prefix(&self, prefix: &ViewPath<'a>) -> Option<ViewPath<'a>> {
+        if !self.starts_with(prefix) {
+            return None;
+        }
+        Some(ViewPath::from(
+            self.segments
+                .range(prefix.len()..)
+                .cloned()
+                .collect::<VecDeque<_>>(),
+        ))
+    }
+
+    /// Returns the longest path both paths start with.
+    pub fn common_prefix(&self, other: &ViewPath<'a>) -> ViewPath<'a> {
+        ViewPath::from(
+            self.segments
+                .iter()
+                .zip(other.iter())
+                .take_while(|(s, o)| s == o)
+                .map(|(s, _)| s.clone())
+                .collect::<VecDeque<_>>(),
+        )
+    }
+
+    /// Returns the path followed by the segments of `other`.
+    pub fn join(&self, other: &ViewPath<'a>) -> ViewPath<'a> {
+        let mut path = self.clone();
+        path.segments.extend(other.iter().cloned());
+        path
     }
 
     pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, Segment<'a>> {
@@ -92,6 +173,26 @@ impl<'a> ViewPath<'a> {
     pub fn into_buf(self) -> ViewPathBuf {
         ViewPathBuf::from(self)
     }
+
+    /// Detaches the path from the string it was parsed from, so it can
+    /// outlive it.
+    pub fn into_owned(self) -> ViewPath<'static> {
+        ViewPath {
+            segments: self.segments.into_iter().map(Segment::into_owned).collect(),
+        }
+    }
+
+    /// Deserializes the path borrowing its fields from the input when the
+    /// format allows it, and copying them otherwise. Use it with
+    /// `#[serde(borrow, deserialize_with = "ViewPath::deserialize_borrowed")]`.
+    pub fn deserialize_borrowed<'de: 'a, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(ViewPathVisitor {
+            _marker: Default::default(),
+        })
+    }
 }
 
 impl<'a> IntoIterator for ViewPath<'a> {
@@ -186,14 +287,14 @@ impl<'a> Serialize for ViewPath<'a> {
     }
 }
 
-impl<'de> Deserialize<'de> for ViewPath<'de> {
-    fn deserialize<D>(deserializer: D) -> Result<ViewPath<'de>, D::Error>
+// Like `Cow`, the path is copied out of the input so it deserializes
+// from any format, see `ViewPath::deserialize_borrowed` to borrow it.
+impl<'de, 'a> Deserialize<'de> for ViewPath<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<ViewPath<'a>, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        deserializer.deserialize_str(ViewPathVisitor {
-            _marker: Default::default(),
-        })
+        deserializer.deserialize_str(OwnedViewPathVisitor)
     }
 }
 
@@ -228,6 +329,32 @@ impl<'de> Visitor<'de> for ViewPathVisitor<'de> {
     {
         ViewPath::parse_str(value).map_err(de::Error::custom)
     }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        OwnedViewPathVisitor.visit_str(value)
+    }
+}
+
+struct OwnedViewPathVisitor;
+
+impl<'de> Visitor<'de> for OwnedViewPathVisitor {
+    type Value = ViewPath<'static>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("not a view path str")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        ViewPath::parse_str(value)
+            .map(ViewPath::into_owned)
+            .map_err(de::Error::custom)
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -237,11 +364,14 @@ pub struct ViewPathBuf {
 
 impl fmt::Display for ViewPathBuf {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return f.write_str("$");
+        }
         let mut peeker = self.segments.iter().peekable();
         while let Some(segment) = peeker.next() {
             let maybe_next = peeker
                 .peek()
-                .map(|next| next.is_field() || next.is_coalesce())
+                .map(|next| next.is_field() || next.is_coalesce() || next.is_wildcard())
                 .unwrap_or(false);
             match (segment, maybe_next) {
                 (SegmentBuf::Field(_), true) => write!(f, r#"{}."#, segment)?,
@@ -250,6 +380,13 @@ impl fmt::Display for ViewPathBuf {
                 (SegmentBuf::Index(_), false) => write!(f, "[{}]", segment)?,
                 (SegmentBuf::Coalesce(_), true) => write!(f, r#"{}."#, segment)?,
                 (SegmentBuf::Coalesce(_), false) => write!(f, "{}", segment)?,
+                (SegmentBuf::Wildcard, true) => write!(f, r#"{}."#, segment)?,
+                (SegmentBuf::Wildcard, false) => write!(f, "{}", segment)?,
+                (SegmentBuf::Descendant, _) => write!(f, "{}", segment)?,
+                (SegmentBuf::Slice(_), true) => write!(f, r#"[{}]."#, segment)?,
+                (SegmentBuf::Slice(_), false) => write!(f, "[{}]", segment)?,
+                (SegmentBuf::Filter(_), true) => write!(f, r#"[{}]."#, segment)?,
+                (SegmentBuf::Filter(_), false) => write!(f, "[{}]", segment)?,
             }
         }
         Ok(())
@@ -299,8 +436,79 @@ impl ViewPathBuf {
         ViewPath::parse_str(input).map(|vp| vp.into_buf())
     }
 
+    /// Returns true if the path addresses at most one value, that is it
+    /// has no wildcard, descendant, slice or filter segments.
+    pub fn is_singular(&self) -> bool {
+        self.segments.iter().all(|s| s.is_singular())
+    }
+
+    /// Returns the number of segments, the root has none.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns true if the path has no segments, like `is_root`.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the path without its last segment, or `None` for the root.
+    pub fn parent(&self) -> Option<ViewPathBuf> {
+        let mut parent = self.clone();
+        parent.pop_back().map(|_| parent)
+    }
+
+    /// Returns the last segment, or `None` for the root.
+    pub fn last(&self) -> Option<&SegmentBuf> {
+        self.segments.back()
+    }
+
+    /// Returns true if the path begins with all the segments of `needle`.
     pub fn starts_with(&self, needle: &ViewPathBuf) -> bool {
-        needle.iter().zip(&self.segments).all(|(n, s)| n == s)
+        needle.len() <= self.len() && needle.iter().zip(&self.segments).all(|(n, s)| n == s)
+    }
+
+    /// Returns true if the path ends with all the segments of `needle`.
+    pub fn ends_with(&self, needle: &ViewPathBuf) -> bool {
+        needle.len() <= self.len()
+            && needle
+                .iter()
+                .rev()
+                .zip(self.segments.iter().rev())
+                .all(|(n, s)| n == s)
+    }
+
+    /// Returns the rest of the path after `prefix`, or `None` if the path
+    /// does not start with it.
+    pub fn strip_prefix(&self, prefix: &ViewPathBuf) -> Option<ViewPathBuf> {
+        if !self.starts_with(prefix) {
+            return None;
+        }
+        Some(ViewPathBuf::from(
+            self.segments
+                .range(prefix.len()..)
+                .cloned()
+                .collect::<VecDeque<_>>(),
+        ))
+    }
+
+    /// Returns the longest path both paths start with.
+    pub fn common_prefix(&self, other: &ViewPathBuf) -> ViewPathBuf {
+        ViewPathBuf::from(
+            self.segments
+                .iter()
+                .zip(other.iter())
+                .take_while(|(s, o)| s == o)
+                .map(|(s, _)| s.clone())
+                .collect::<VecDeque<_>>(),
+        )
+    }
+
+    /// Returns the path followed by the segments of `other`.
+    pub fn join(&self, other: &ViewPathBuf) -> ViewPathBuf {
+        let mut path = self.clone();
+        path.segments.extend(other.iter().cloned());
+        path
     }
 
     pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, SegmentBuf> {
@@ -459,7 +667,14 @@ impl<'de> Visitor<'de> for ViewPathBufVisitor {
 
 #[cfg(test)]
 mod tests {
-    use crate::ViewPathBuf;
+    use std::{borrow::Cow, collections::VecDeque};
+
+    use serde::Deserialize;
+
+    use crate::{
+        CompareOp, FieldBuf, Filter, Literal, Operand, Segment, SegmentBuf, Slice, ViewPath,
+        ViewPathBuf,
+    };
 
     #[test]
     fn test_parse_str() {
@@ -475,4 +690,354 @@ mod tests {
         let e = view_path.pop_front().unwrap();
         assert_eq!(true, e.is_index());
     }
+
+    #[test]
+    fn test_parse_wildcard() {
+        let view_path = ViewPathBuf::parse_str("foo.*.name").unwrap();
+        assert!(view_path[1].is_wildcard());
+        assert!(!view_path.is_singular());
+        assert_eq!("foo.*.name", view_path.to_string());
+
+        let view_path = ViewPathBuf::parse_str("items[*].id").unwrap();
+        assert!(view_path[1].is_wildcard());
+        assert_eq!(ViewPathBuf::parse_str("items.*.id").unwrap(), view_path);
+
+        assert!(ViewPathBuf::parse_str("a.b[0]").unwrap().is_singular());
+    }
+
+    #[test]
+    fn test_parse_descendant() {
+        let view_path = ViewPathBuf::parse_str("a..id").unwrap();
+        assert!(view_path[1].is_descendant());
+        assert!(view_path[2].is_field());
+        assert!(!view_path.is_singular());
+
+        for path in [
+            "..id",
+            "a..id",
+            "a..*",
+            "a..[0].b",
+            "..(a | b)",
+            "a[1]..b..c",
+        ] {
+            let view_path = ViewPathBuf::parse_str(path).unwrap();
+            assert_eq!(path, view_path.to_string());
+        }
+        assert!(ViewPathBuf::parse_str("a...b").is_err());
+    }
+
+    #[test]
+    fn test_parse_slice() {
+        let view_path = ViewPathBuf::parse_str("logs[:10]").unwrap();
+        assert_eq!(
+            SegmentBuf::slice(Slice::new(None, Some(10), None)),
+            view_path[1]
+        );
+        assert!(!view_path.is_singular());
+
+        let view_path = ViewPathBuf::parse_str("a[-3:-1:2].b").unwrap();
+        assert_eq!(
+            SegmentBuf::slice(Slice::new(Some(-3), Some(-1), Some(2))),
+            view_path[1]
+        );
+
+        for path in [
+            "a[:]",
+            "a[1:]",
+            "a[::2]",
+            "a[::-1]",
+            "a[1:2:3].b",
+            "a..[:1]",
+        ] {
+            let view_path = ViewPathBuf::parse_str(path).unwrap();
+            assert_eq!(path, view_path.to_string());
+        }
+
+        for path in ["a[99999999999999999999]", "a[1:99999999999999999999]"] {
+            let err = ViewPathBuf::parse_str(path).unwrap_err();
+            assert!(
+                err.to_string().contains("the index is too large"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let view_path = ViewPathBuf::parse_str(r#"items[?(@.status == "active")].id"#).unwrap();
+        assert_eq!(
+            SegmentBuf::filter(Filter::compare(
+                Operand::Path(ViewPathBuf::from("status")),
+                CompareOp::Eq,
+                Operand::Literal(Literal::String("active".into())),
+            )),
+            view_path[1]
+        );
+        assert!(!view_path.is_singular());
+
+        for path in [
+            r#"a[?(@.status == "active")].id"#,
+            r#"a[?(@.n >= 1.5 && @.n < -2 || !@.x)]"#,
+            r#"a[?(@.a && (@.b || @.c))]"#,
+            r#"a[?(!(@[0] != null) && @..id =~ "^a")]"#,
+            r#"a[?(@ == true)]"#,
+            r#"a[?(@.(a | b) > @.c)]"#,
+            r#"null.true[?(@.false)]"#,
+        ] {
+            let view_path = ViewPathBuf::parse_str(path).unwrap();
+            assert_eq!(path, view_path.to_string());
+        }
+
+        let grouped = ViewPathBuf::parse_str("a[?(@.a || (@.b || @.c))]").unwrap();
+        assert_eq!(
+            grouped,
+            ViewPathBuf::parse_str(&grouped.to_string()).unwrap()
+        );
+        assert!(ViewPathBuf::parse_str("a[?(@.a ==)]").is_err());
+
+        let view_path = ViewPathBuf::parse_str(r#"a[?(@.b =~ "^x\\.")]"#).unwrap();
+        match &view_path[1] {
+            SegmentBuf::Filter(filter) => match &**filter {
+                Filter::Compare(_, CompareOp::Match, Operand::Pattern(p)) => {
+                    assert!(p.is_match("x.y"));
+                    assert!(!p.is_match("xy"));
+                }
+                other => panic!("unexpected {:?}", other),
+            },
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(r#"a[?(@.b =~ "^x\\.")]"#, view_path.to_string());
+        let err = ViewPathBuf::parse_str(r#"a[?(@.b =~ "(")]"#).unwrap_err();
+        assert!(err.to_string().contains("the regex is invalid"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_escaped_field() {
+        let view_path = ViewPathBuf::parse_str(r#"a."b\"c".d"#).unwrap();
+        match &view_path[1] {
+            SegmentBuf::Field(field) => assert_eq!("b\"c", field.as_str()),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(r#"a."b\"c".d"#, view_path.to_string());
+
+        let view_path = ViewPathBuf::parse_str(r#"a[?(@.b == "x\"y")]"#).unwrap();
+        assert_eq!(r#"a[?(@.b == "x\"y")]"#, view_path.to_string());
+
+        // A stray backslash is rejected rather than dropped.
+        let err = ViewPathBuf::parse_str(r#"k."a\b""#).unwrap_err();
+        assert!(err.to_string().contains("unknown escape"), "{}", err);
+        assert!(err.to_string().contains("\n    ^^^^^"), "{}", err);
+        assert!(ViewPathBuf::parse_str(r#"a[?(@.b == "x\q")]"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_regex_escapes() {
+        let err = ViewPathBuf::parse_str(r#"l[?(@.v =~ "^\d+$")]"#).unwrap_err();
+        assert!(err.to_string().contains("unknown escape"), "{}", err);
+
+        let view_path = ViewPathBuf::parse_str(r#"l[?(@.v =~ "^\\d+$")]"#).unwrap();
+        match &view_path[1] {
+            SegmentBuf::Filter(filter) => match &**filter {
+                Filter::Compare(_, CompareOp::Match, Operand::Pattern(p)) => {
+                    assert!(p.is_match("123"));
+                    assert!(!p.is_match("ddd"));
+                }
+                other => panic!("unexpected {:?}", other),
+            },
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(r#"l[?(@.v =~ "^\\d+$")]"#, view_path.to_string());
+    }
+
+    #[test]
+    fn test_parse_unicode_and_empty_field() {
+        let view_path = ViewPathBuf::parse_str(r#"名前.café."".x"#).unwrap();
+        let names = view_path
+            .iter()
+            .map(|s| match s {
+                SegmentBuf::Field(f) => f.as_str(),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["名前", "café", "", "x"], names);
+        assert_eq!(r#"名前.café."".x"#, view_path.to_string());
+        assert!(ViewPathBuf::parse_str(r#""""#).is_ok());
+        assert!(ViewPathBuf::parse_str("a.🦀").is_err());
+    }
+
+    #[test]
+    fn test_path_arithmetic() {
+        let path = |p| ViewPathBuf::parse_str(p).unwrap();
+        let abc = path("a.b[0]");
+        assert_eq!(3, abc.len());
+        assert!(path("$").is_empty());
+        assert_eq!(Some(path("a.b")), abc.parent());
+        assert_eq!(Some(path("$")), path("a").parent());
+        assert_eq!(None, path("$").parent());
+        assert_eq!(Some(&SegmentBuf::index(0)), abc.last());
+        assert_eq!(None, path("$").last());
+
+        assert!(abc.starts_with(&path("a.b")));
+        assert!(abc.starts_with(&path("$")));
+        assert!(abc.starts_with(&abc));
+        assert!(!path("a").starts_with(&path("a.b")));
+        assert!(!abc.starts_with(&path("b")));
+        assert!(abc.ends_with(&path("b[0]")));
+        assert!(abc.ends_with(&path("$")));
+        assert!(!path("[0]").ends_with(&path("b[0]")));
+        assert!(!abc.ends_with(&path("a.b")));
+
+        assert_eq!(Some(path("[0]")), abc.strip_prefix(&path("a.b")));
+        assert_eq!(Some(path("$")), abc.strip_prefix(&abc));
+        assert_eq!(None, abc.strip_prefix(&path("a.c")));
+        assert_eq!(None, path("a").strip_prefix(&path("a.b")));
+
+        assert_eq!(path("a.b"), abc.common_prefix(&path("a.b.c")));
+        assert_eq!(path("$"), abc.common_prefix(&path("b")));
+        assert_eq!(abc, path("a").join(&path("b[0]")));
+        assert_eq!(abc, abc.join(&path("$")));
+
+        let from = path("a.b[0].c");
+        let to = path("a.b[1]");
+        let common = from.common_prefix(&to);
+        assert_eq!(Some(path("[1]")), to.strip_prefix(&common));
+        assert_eq!(to, common.join(&to.strip_prefix(&common).unwrap()));
+
+        let abc = ViewPath::parse_str("a.b[0]").unwrap();
+        assert_eq!(3, abc.len());
+        assert_eq!(Some(ViewPath::parse_str("a.b").unwrap()), abc.parent());
+        assert!(!ViewPath::from("a").starts_with(&abc));
+        assert!(abc.ends_with(&ViewPath::parse_str("[0]").unwrap()));
+        assert_eq!(
+            Some(ViewPath::parse_str("b[0]").unwrap()),
+            abc.strip_prefix(&ViewPath::from("a"))
+        );
+        assert_eq!(
+            ViewPath::from("a"),
+            abc.common_prefix(&ViewPath::parse_str("a.c").unwrap())
+        );
+        assert_eq!(
+            abc,
+            ViewPath::from("a").join(&ViewPath::parse_str("b[0]").unwrap())
+        );
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Lookup<'a> {
+        path: ViewPath<'a>,
+        paths: Vec<ViewPathBuf>,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct BorrowedLookup<'a> {
+        #[serde(borrow, deserialize_with = "ViewPath::deserialize_borrowed")]
+        path: ViewPath<'a>,
+    }
+
+    #[test]
+    fn test_deserialize_transient_str() {
+        let lookup = Lookup {
+            path: ViewPath::parse_str(r#"a."b c"[0]..id"#).unwrap(),
+            paths: vec![ViewPathBuf::parse_str("x.*").unwrap()],
+        };
+
+        let json = serde_json::to_string(&lookup).unwrap();
+        assert_eq!(r#"{"path":"a.\"b c\"[0]..id","paths":["x.*"]}"#, json);
+        assert_eq!(lookup, serde_json::from_str(&json).unwrap());
+        let read: Lookup<'static> = serde_json::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(lookup, read);
+        let mut de = serde_json::Deserializer::from_reader(&b"\"a.b\""[..]);
+        assert_eq!(
+            ViewPath::parse_str("a.b").unwrap(),
+            ViewPath::deserialize(&mut de).unwrap()
+        );
+
+        // Borrowed from the input unless the string has escapes.
+        let borrowed: BorrowedLookup = serde_json::from_str(r#"{"path":"a.b"}"#).unwrap();
+        assert!(
+            matches!(&borrowed.path[1], Segment::Field(f) if matches!(f.name, Cow::Borrowed(_)))
+        );
+        let borrowed: BorrowedLookup = serde_json::from_str(r#"{"path":"a.\"b\""}"#).unwrap();
+        assert_eq!(ViewPath::parse_str(r#"a."b""#).unwrap(), borrowed.path);
+        let mut de = serde_json::Deserializer::from_reader(&br#"{"path":"a.b"}"#[..]);
+        let borrowed = BorrowedLookup::deserialize(&mut de).unwrap();
+        assert_eq!(ViewPath::parse_str("a.b").unwrap(), borrowed.path);
+
+        let toml = toml::to_string(&lookup).unwrap();
+        assert_eq!(lookup, toml::from_str(&toml).unwrap());
+        let toml = "path = 'a.b[0]'\npaths = [\"x.\\\"y\\\"\"]";
+        assert_eq!(
+            Lookup {
+                path: ViewPath::parse_str("a.b[0]").unwrap(),
+                paths: vec![ViewPathBuf::parse_str(r#"x."y""#).unwrap()],
+            },
+            toml::from_str(toml).unwrap()
+        );
+
+        let yaml = serde_yaml::to_string(&lookup).unwrap();
+        assert_eq!(lookup, serde_yaml::from_str(&yaml).unwrap());
+        assert_eq!(
+            ViewPath::parse_str("a[1:]").unwrap(),
+            serde_yaml::from_str::<ViewPath>("a[1:]").unwrap()
+        );
+        assert!(serde_yaml::from_str::<ViewPath>("a[").is_err());
+    }
+
+    #[test]
+    fn test_parse_root_and_anchor() {
+        for path in ["", ".", "$", "$."] {
+            assert_eq!(ViewPathBuf::root(), ViewPathBuf::parse_str(path).unwrap());
+        }
+        assert_eq!("$", ViewPathBuf::root().to_string());
+        assert_eq!(
+            ViewPathBuf::parse_str("a.b").unwrap(),
+            ViewPathBuf::parse_str("$.a.b").unwrap()
+        );
+        assert_eq!(
+            ViewPathBuf::parse_str("[0].name").unwrap(),
+            ViewPathBuf::parse_str("$[0].name").unwrap()
+        );
+        assert_eq!(
+            ViewPathBuf::parse_str("..a").unwrap(),
+            ViewPathBuf::parse_str("$..a").unwrap()
+        );
+        for path in [
+            "$",
+            "[0].name",
+            "[-1][2]",
+            "*.a",
+            "[1:]",
+            "[?(@)]",
+            "a[?(@ == 1)]",
+        ] {
+            let view_path = ViewPathBuf::parse_str(path).unwrap();
+            assert_eq!(path, view_path.to_string());
+        }
+        for path in ["a.", "a$", "a.$", "$$", "..", "@"] {
+            assert!(ViewPathBuf::parse_str(path).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_display_parse_quickcheck() {
+        fn test_display_parse(fields: Vec<FieldBuf>) -> quickcheck::TestResult {
+            if fields.is_empty() {
+                return quickcheck::TestResult::discard();
+            }
+            let view_path = ViewPathBuf::from(
+                fields
+                    .into_iter()
+                    .map(SegmentBuf::Field)
+                    .collect::<VecDeque<_>>(),
+            );
+            let parsed = ViewPathBuf::parse_str(&view_path.to_string()).unwrap();
+            assert_eq!(view_path, parsed);
+            quickcheck::TestResult::passed()
+        }
+        quickcheck::QuickCheck::new()
+            .tests(500)
+            .max_tests(1_000)
+            .quickcheck(test_display_parse as fn(Vec<FieldBuf>) -> quickcheck::TestResult)
+    }
 }
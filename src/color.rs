@@ -0,0 +1,229 @@
+//! Colorizes the json output, as jq does.
+//!
+//! The colors are left to `colored`, which turns them off when stdout is
+//! not a terminal or `NO_COLOR` is set, unless `--color` says otherwise.
+
+use std::fmt::{Display, Write};
+
+use clap::ArgEnum;
+use colored::{ColoredString, Colorize};
+use regex::Regex;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, ArgEnum)]
+pub enum ColorChoice {
+    /// Colors when stdout is a terminal and `NO_COLOR` is not set.
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Overrides the detection of `colored` for the whole process.
+    pub fn apply(self) {
+        match self {
+            ColorChoice::Auto => {}
+            ColorChoice::Always => colored::control::set_override(true),
+            ColorChoice::Never => colored::control::set_override(false),
+        }
+    }
+}
+
+/// Renders the value as `serde_json::to_string_pretty` does, with its keys,
+/// strings, numbers, literals and punctuation colored.
+pub fn to_colored_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, Some(0));
+    out
+}
+
+/// Renders the value on a single line as `serde_json::to_string` does, with
+/// the colors of `to_colored_json`.
+pub fn to_colored_json_compact(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, None);
+    out
+}
+
+/// Renders the leaf compactly, with the matches of the regex highlighted.
+/// The regex is matched against the text of the leaf, as `nest_find_regex`
+/// does, so the matches of a string are found before escaping it.
+pub fn highlight(value: &Value, reg: &Regex) -> String {
+    let (text, quoted) = match value {
+        Value::String(s) => (s.clone(), true),
+        value => (value.to_string(), false),
+    };
+    let escape = |s: &str| {
+        if quoted {
+            let s = Value::String(s.to_string()).to_string();
+            s[1..s.len() - 1].to_string()
+        } else {
+            s.to_string()
+        }
+    };
+    let mut out = String::new();
+    if quoted {
+        push(&mut out, paint(value, "\""));
+    }
+    let mut last = 0;
+    for m in reg.find_iter(&text) {
+        if m.start() > last {
+            push(&mut out, paint(value, &escape(&text[last..m.start()])));
+        }
+        if !m.as_str().is_empty() {
+            push(&mut out, escape(m.as_str()).red().bold().underline());
+        }
+        last = m.end();
+    }
+    if last < text.len() {
+        push(&mut out, paint(value, &escape(&text[last..])));
+    }
+    if quoted {
+        push(&mut out, paint(value, "\""));
+    }
+    out
+}
+
+/// Colors the text of a leaf by the type of the leaf.
+fn paint(value: &Value, text: &str) -> ColoredString {
+    match value {
+        Value::Null => text.bright_black(),
+        Value::Bool(_) => text.yellow(),
+        Value::Number(_) => text.cyan(),
+        Value::String(_) => text.green(),
+        Value::Array(_) | Value::Object(_) => text.bold(),
+    }
+}
+
+fn push(out: &mut String, s: impl Display) {
+    let _ = write!(out, "{}", s);
+}
+
+/// Breaks the line before a child at the indent, if pretty.
+fn newline(out: &mut String, indent: Option<usize>) {
+    if let Some(indent) = indent {
+        out.push('\n');
+        for _ in 0..indent {
+            out.push_str("  ");
+        }
+    }
+}
+
+/// Writes the value, pretty at the indent or compact if it is `None`.
+fn write_value(out: &mut String, value: &Value, indent: Option<usize>) {
+    let inner = indent.map(|indent| indent + 1);
+    match value {
+        Value::Array(a) if a.is_empty() => push(out, paint(value, "[]")),
+        Value::Array(a) => {
+            push(out, paint(value, "["));
+            for (i, v) in a.iter().enumerate() {
+                if i > 0 {
+                    push(out, paint(value, ","));
+                }
+                newline(out, inner);
+                write_value(out, v, inner);
+            }
+            newline(out, indent);
+            push(out, paint(value, "]"));
+        }
+        Value::Object(o) if o.is_empty() => push(out, paint(value, "{}")),
+        Value::Object(o) => {
+            push(out, paint(value, "{"));
+            for (i, (k, v)) in o.iter().enumerate() {
+                if i > 0 {
+                    push(out, paint(value, ","));
+                }
+                newline(out, inner);
+                push(out, Value::String(k.clone()).to_string().blue().bold());
+                push(out, paint(value, ":"));
+                if indent.is_some() {
+                    out.push(' ');
+                }
+                write_value(out, v, inner);
+            }
+            newline(out, indent);
+            push(out, paint(value, "}"));
+        }
+        leaf => push(out, paint(leaf, &leaf.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use colored::Colorize;
+    use regex::Regex;
+    use serde_json::{json, Value};
+
+    use super::{highlight, to_colored_json, to_colored_json_compact};
+
+    /// Serializes the tests, as the override of `colored` is global.
+    static OVERRIDE: Mutex<()> = Mutex::new(());
+
+    fn with_colors(on: bool, f: impl FnOnce()) {
+        let _guard = OVERRIDE.lock().unwrap_or_else(|e| e.into_inner());
+        colored::control::set_override(on);
+        f();
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_to_colored_json_uncolored() {
+        with_colors(false, || {
+            for value in [
+                json!({"a": {"b": [1, 2.5, null, true], "c": {}}, "d": []}),
+                json!({}),
+                json!([]),
+                json!([[], {}, [{}]]),
+                json!({"a\"b": "c\\d\n", "": "é\u{1}"}),
+                json!("x"),
+            ] {
+                assert_eq!(
+                    serde_json::to_string_pretty(&value).unwrap(),
+                    to_colored_json(&value)
+                );
+                assert_eq!(
+                    serde_json::to_string(&value).unwrap(),
+                    to_colored_json_compact(&value)
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_highlight_uncolored() {
+        with_colors(false, || {
+            let reg = Regex::new("a|\"|x*").unwrap();
+            for value in [json!("abc"), json!("a\"b\n"), json!(12), json!(null)] {
+                assert_eq!(value.to_string(), highlight(&value, &reg));
+            }
+        });
+    }
+
+    #[test]
+    fn test_highlight() {
+        with_colors(true, || {
+            let hit = |s: &str| s.red().bold().underline().to_string();
+            let text = |s: &str| s.green().to_string();
+
+            let reg = Regex::new("b+").unwrap();
+            let expected = [text("\""), text("a"), hit("bb"), text("c"), text("\"")];
+            assert_eq!(expected.concat(), highlight(&json!("abbc"), &reg));
+
+            // The matches are found in the text and escaped after.
+            let reg = Regex::new("\"").unwrap();
+            let expected = [text("\""), text("a"), hit("\\\""), text("b"), text("\"")];
+            assert_eq!(expected.concat(), highlight(&json!("a\"b"), &reg));
+
+            // The empty matches highlight nothing.
+            let reg = Regex::new("x*").unwrap();
+            let expected = [text("\""), text("a"), text("b"), text("\"")];
+            assert_eq!(expected.concat(), highlight(&json!("ab"), &reg));
+
+            let reg = Regex::new("2").unwrap();
+            let expected = ["1".cyan().to_string(), hit("2"), "3".cyan().to_string()];
+            assert_eq!(expected.concat(), highlight(&Value::from(123), &reg));
+        });
+    }
+}
@@ -1,16 +1,36 @@
+pub mod color;
 pub mod tools;
 
 use std::{
+    fmt::Display,
     fs,
+    io::{self, Read},
     path::{Path, PathBuf},
+    process::ExitCode,
 };
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Args, Parser};
 
-pub struct Context {}
+pub struct Context {
+    /// Prints no results nor misses, the exit status alone tells the
+    /// outcome. Errors and warnings are still printed, as with grep.
+    pub quiet: bool,
+}
 
 impl Context {
+    /// Prints a line of the results, unless quiet.
+    pub fn println(&self, line: impl Display) {
+        if !self.quiet {
+            println!("{}", line);
+        }
+    }
+
+    /// Prints a line of the diagnostics, even if quiet.
+    pub fn eprintln(&self, line: impl Display) {
+        eprintln!("{}", line);
+    }
+
     pub fn full_extend(&self, path: &str) -> Result<PathBuf> {
         let full = shellexpand::full(path)?;
         Ok(Path::new(full.as_ref()).to_path_buf())
@@ -22,12 +42,121 @@ impl Context {
         let text = fs::read_to_string(path)?;
         Ok(text)
     }
+
+    /// Reads the file, or the standard input if the path is `-`.
+    pub fn read_input(&self, path: &str) -> Result<String> {
+        if path == "-" {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            return Ok(text);
+        }
+        self.read_to_string(path)
+    }
+
+    pub fn write(&self, path: &str, contents: &str) -> Result<()> {
+        let full = shellexpand::full(path)?;
+        let path = Path::new(full.as_ref());
+        fs::write(path, contents)?;
+        Ok(())
+    }
 }
 
 pub trait TryRun {
     type Err;
 
     fn run(&self, ctx: &Context) -> Result<(), Self::Err>;
+
+    /// Runs the command and reports its error, returning the exit status of
+    /// the process.
+    fn exit(&self, ctx: &Context) -> Status
+    where
+        Self::Err: Into<anyhow::Error>,
+    {
+        match self.run(ctx) {
+            Ok(()) => Status::Found,
+            Err(e) => {
+                let e = e.into();
+                let status = Status::of(&e);
+                // As with grep, a miss is quiet too, only the status tells it.
+                if !(ctx.quiet && status == Status::NotFound) {
+                    ctx.eprintln(&e);
+                }
+                status
+            }
+        }
+    }
+}
+
+/// The exit status of the binaries, which follows grep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    /// The command succeeded, searchs found something.
+    Found = 0,
+    /// Searchs found nothing, or the path to edit is missing.
+    NotFound = 1,
+    /// The command line, a path or an input is invalid.
+    Usage = 2,
+    /// A file could not be read or written.
+    Io = 3,
+}
+
+impl Status {
+    /// Classifies the error by the first error of its chain which tells a
+    /// status, errors which tell none are usage errors.
+    pub fn of(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if let Some(failure) = cause.downcast_ref::<Failure>() {
+                return failure.status;
+            }
+            if let Some(e) = cause.downcast_ref::<ejson::Error>() {
+                return match e {
+                    ejson::Error::NotSingular { .. } | ejson::Error::InvalidJson { .. } => {
+                        Status::Usage
+                    }
+                    _ => Status::NotFound,
+                };
+            }
+            if cause.is::<io::Error>() {
+                return Status::Io;
+            }
+            if let Some(e) = cause.downcast_ref::<serde_json::Error>() {
+                if e.is_io() {
+                    return Status::Io;
+                }
+            }
+        }
+        Status::Usage
+    }
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// An error which stands for an exit status.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct Failure {
+    pub status: Status,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: Status::NotFound,
+            message: message.into(),
+        }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self {
+            status: Status::Io,
+            message: message.into(),
+        }
+    }
 }
 
 #[macro_export]
@@ -53,16 +182,43 @@ macro_rules! define_sub_commands {
     };
 }
 
-/// Validates, formats or searchs the json file.
+/// The options shared by the binaries.
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// Prints no results, the exit status alone tells the outcome: 0 when
+    /// something is found, 1 when nothing is, 2 on invalid arguments, paths
+    /// or inputs and 3 when a file cannot be read or written. Errors are
+    /// still printed, misses are not.
+    #[clap(long, short, global = true)]
+    pub quiet: bool,
+
+    /// Colors the json output, `auto` colors it on a terminal unless
+    /// `NO_COLOR` is set.
+    #[clap(long, arg_enum, global = true, default_value = "auto")]
+    pub color: color::ColorChoice,
+}
+
+impl GlobalArgs {
+    /// Applies the color choice and returns the context of the commands.
+    pub fn context(&self) -> Context {
+        self.color.apply();
+        Context { quiet: self.quiet }
+    }
+}
+
+/// Validates, formats or searchs the json, toml and yaml files.
 #[derive(Debug, Parser)]
 pub struct App {
+    #[clap(flatten)]
+    pub global: GlobalArgs,
+
     #[clap(subcommand)]
     sub_commands: SubCommands,
 }
 
-use tools::json::Json;
+use tools::{json::Json, toml::Toml, yaml::Yaml};
 
-define_sub_commands! {SubCommands, Json}
+define_sub_commands! {SubCommands, Json, Toml, Yaml}
 
 impl crate::TryRun for App {
     type Err = anyhow::Error;
@@ -71,3 +227,35 @@ impl crate::TryRun for App {
         self.sub_commands.run(ctx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use view::ViewPath;
+
+    use crate::{Context, Failure, Status};
+
+    #[test]
+    fn test_status_of() {
+        let value = json!({"a": [1, 2]});
+        let missing = ejson::search_path(&value, ViewPath::parse_str("b").unwrap()).unwrap_err();
+        assert_eq!(Status::NotFound, Status::of(&missing.into()));
+
+        let bad_path = ViewPath::parse_str("a[").unwrap_err();
+        assert_eq!(Status::Usage, Status::of(&bad_path.into()));
+
+        let not_singular =
+            ejson::search_path(&value, ViewPath::parse_str("a[*]").unwrap()).unwrap_err();
+        assert_eq!(Status::Usage, Status::of(&not_singular.into()));
+
+        let ctx = Context { quiet: true };
+        let missing_file = ctx.read_to_string("/nonexistent/ebar.json").unwrap_err();
+        assert_eq!(Status::Io, Status::of(&missing_file));
+
+        let invalid = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(Status::Usage, Status::of(&invalid.into()));
+
+        let failure = anyhow::Error::new(Failure::not_found("nothing")).context("in a.json");
+        assert_eq!(Status::NotFound, Status::of(&failure));
+    }
+}
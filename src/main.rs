@@ -1,10 +1,10 @@
+use std::process::ExitCode;
+
 use clap::Parser;
-use ebar::{Context, TryRun};
+use ebar::TryRun;
 
-fn main() {
+fn main() -> ExitCode {
     let app = ebar::App::parse();
-    let ctx = Context {};
-    if let Err(e) = app.run(&ctx) {
-        eprintln!("{}", &e);
-    }
+    let ctx = app.global.context();
+    app.exit(&ctx).into()
 }
@@ -1,84 +1,468 @@
-use clap::{Args, Parser};
+use std::{fmt::Write, fs};
+
+use clap::{ArgGroup, Args, Parser};
+use ejson::SpanMap;
 use serde_json::Value;
-use view::ViewPath;
+use view::ViewPathBuf;
+use walkdir::WalkDir;
+
+use super::search::{Output, PathFormat, PathSyntax};
+use crate::{color, Failure, Status};
 
 /// Searchs with the specified path from the json file if the path
 /// is specified. Otherwise, show the pretty json string.
+///
+/// A path with wildcards (`foo.*.name`, `items[*].id`), slices (`logs[:10]`),
+/// descendants (`..id`) or filters (`items[?(@.status == "active")]`) may
+/// match several values, each of them is printed after its concrete path.
+///
+/// Several json files, or whole directories with --recursive, may be
+/// searched at once, each line of the results is then prefixed by the name
+/// of its file.
+/// With --vimgrep, they are prefixed by the line and column of their value
+/// too, and the values are printed on a single line.
 #[derive(Debug, Args)]
 pub struct Search {
     /// if --path or -p is specified, searchs from the json file.
     #[clap(long, short)]
     path: Option<String>,
 
-    #[clap(name = "json-file")]
-    file: String,
+    /// The syntax of the path, the matched paths are printed in it too.
+    #[clap(long, arg_enum, default_value = "path")]
+    syntax: PathSyntax,
+
+    #[clap(flatten)]
+    inputs: Inputs,
 }
 
 impl crate::TryRun for Search {
     type Err = anyhow::Error;
 
     fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
-        let data = ctx.read_to_string(&self.file)?;
-        let value = serde_json::from_str::<Value>(&data)?;
-        match if let Some(path) = &self.path {
-            ejson::search_path(&value, ViewPath::parse_str(path)?)
+        self.inputs
+            .for_each(ctx, |source, value| self.search(ctx, source, value))
+    }
+}
+
+impl Search {
+    fn search(&self, ctx: &crate::Context, source: &Source, value: &Value) -> anyhow::Result<()> {
+        // Editors read a hit of --vimgrep per line, so values are compact.
+        let render = if self.inputs.vimgrep {
+            color::to_colored_json_compact
         } else {
-            Some(&value)
-        } {
-            Some(v) => {
-                println!("{}", &serde_json::to_string_pretty(v)?);
-                Ok(())
+            color::to_colored_json
+        };
+        let output = Output {
+            ctx,
+            render: &|v| Ok(render(v)),
+            prefix: &|path| source.prefix(path),
+        };
+        match &self.path {
+            Some(input) => {
+                let path = self.syntax.parse(input, value)?;
+                output.search(value, input, path, self.syntax, self.inputs.full_paths())?
             }
-            None => Ok(()),
+            None => output.print(&ViewPathBuf::root(), render(value)),
         }
+        Ok(())
     }
 }
 
 /// Resolves the path for the target value from the json file.
 #[derive(Debug, Args)]
+#[clap(group(ArgGroup::new("value").required(true).args(&["target", "regex"])))]
 pub struct Resolve {
     /// The target value to resolve.
     #[clap(long, short)]
     target: Option<String>,
 
     /// The regex target value to resolve.
-    #[clap(long, short, conflicts_with = "target")]
+    #[clap(long, short)]
     regex: Option<String>,
 
-    #[clap(name = "json-file")]
-    file: String,
+    /// The form of the printed paths.
+    #[clap(long, short, arg_enum, default_value = "path")]
+    format: PathFormat,
+
+    #[clap(flatten)]
+    inputs: Inputs,
 }
 
 impl crate::TryRun for Resolve {
     type Err = anyhow::Error;
 
     fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
-        let data = ctx.read_to_string(&self.file)?;
-        let value = serde_json::from_str::<Value>(&data)?;
+        let reg = self.regex.as_deref().map(regex::Regex::new).transpose()?;
+        self.inputs.for_each(ctx, |source, value| {
+            self.resolve(ctx, source, value, reg.as_ref())
+        })
+    }
+}
+
+impl Resolve {
+    fn resolve(
+        &self,
+        ctx: &crate::Context,
+        source: &Source,
+        value: &Value,
+        reg: Option<&regex::Regex>,
+    ) -> anyhow::Result<()> {
+        let output = Output {
+            ctx,
+            render: &|v: &Value| {
+                Ok(reg.map_or_else(|| v.to_string(), |reg| color::highlight(v, reg)))
+            },
+            prefix: &|path| source.prefix(path),
+        };
         if let Some(target) = &self.target {
-            match ejson::nest_find_value(&value, target) {
-                Some(paths) => {
-                    for path in paths {
-                        println!("{}", &path)
+            output.resolve_value(value, target, self.format)?;
+        } else if let Some(reg) = reg {
+            output.resolve_regex(value, reg, self.format)?;
+        }
+        Ok(())
+    }
+}
+
+/// The json inputs of the search commands.
+#[derive(Debug, Args)]
+pub struct Inputs {
+    /// Walks the directories and reads the json files in them.
+    #[clap(long, short = 'R')]
+    recursive: bool,
+
+    /// In recursive mode, only reads the files whose name matches the glob.
+    #[clap(long, short, default_value = "*.json")]
+    glob: String,
+
+    /// Prints each result after the `file:line:column:` of its value, which
+    /// editors can jump to.
+    #[clap(long)]
+    vimgrep: bool,
+
+    /// The json files, `-` or none reads the standard input. In recursive
+    /// mode, none walks the current directory.
+    #[clap(name = "json-file")]
+    files: Vec<String>,
+}
+
+impl Inputs {
+    /// Returns true if every result is printed after its concrete path, as
+    /// the hits of several files, in a tree or not, or the locations of
+    /// --vimgrep need, so their results all read `file:path: value`.
+    fn full_paths(&self) -> bool {
+        self.recursive || self.vimgrep || self.files.len() > 1
+    }
+
+    /// Reads the input as json, with the name prefixing its results if
+    /// `named` or --vimgrep is set.
+    fn source<'a>(
+        &self,
+        name: &'a str,
+        text: &str,
+        named: bool,
+    ) -> anyhow::Result<(Source<'a>, Value)> {
+        let value = serde_json::from_str(text)?;
+        let spans = if self.vimgrep {
+            Some(SpanMap::parse(text)?)
+        } else {
+            None
+        };
+        let name = (named || self.vimgrep).then_some(name);
+        Ok((Source { name, spans }, value))
+    }
+
+    /// Runs `f` on the value of each input, with the source prefixing its
+    /// results, which is named when there are several of them.
+    ///
+    /// With several inputs, the failing ones are reported and skipped, so one
+    /// bad file does not hide the results of the others. As when walking the
+    /// directories, the inputs without results are not reported.
+    fn for_each(
+        &self,
+        ctx: &crate::Context,
+        mut f: impl FnMut(&Source, &Value) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        if self.recursive {
+            return self.walk(ctx, f);
+        }
+        let stdin = ["-".to_string()];
+        let files = if self.files.is_empty() {
+            &stdin[..]
+        } else {
+            &self.files[..]
+        };
+        if let [file] = files {
+            let (source, value) = self.source(name(file), &ctx.read_input(file)?, false)?;
+            return f(&source, &value);
+        }
+        let mut outcome = Outcome::default();
+        for file in files {
+            let name = name(file);
+            let result = ctx
+                .read_input(file)
+                .and_then(|data| self.source(name, &data, true))
+                .and_then(|(source, value)| f(&source, &value));
+            if let Some(e) = outcome.add(&result) {
+                ctx.eprintln(format_args!("{}: {}", name, e));
+            }
+        }
+        outcome.finish()
+    }
+
+    /// Walks the directories in file name order. The files which cannot be
+    /// read as json are skipped with a warning, the named files are read
+    /// whatever their name. The files without results are not reported, the
+    /// directories which cannot be walked fail as i/o errors.
+    fn walk(
+        &self,
+        ctx: &crate::Context,
+        mut f: impl FnMut(&Source, &Value) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let pattern = glob::Pattern::new(&self.glob)?;
+        let cwd = [".".to_string()];
+        let roots = if self.files.is_empty() {
+            &cwd[..]
+        } else {
+            &self.files[..]
+        };
+        let mut outcome = Outcome::default();
+        for root in roots {
+            for entry in WalkDir::new(ctx.full_extend(root)?).sort_by_file_name() {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        let message = e.to_string();
+                        let result = Err(Failure::io(message).into());
+                        if let Some(e) = outcome.add(&result) {
+                            ctx.eprintln(e);
+                        }
+                        continue;
                     }
+                };
+                let matched =
+                    entry.depth() == 0 || pattern.matches(&entry.file_name().to_string_lossy());
+                if !entry.file_type().is_file() || !matched {
+                    continue;
                 }
-                None => println!("{} is not found", target),
-            }
-        } else if let Some(target) = &self.regex {
-            let reg = regex::Regex::new(target)?;
-            match ejson::nest_find_regex(&value, reg) {
-                Some(paths) => {
-                    for (path, v) in paths {
-                        println!("{}: {}", &path, v)
+                let name = entry.path().display().to_string();
+                let source = fs::read_to_string(entry.path())
+                    .map_err(anyhow::Error::from)
+                    .and_then(|data| self.source(&name, &data, true));
+                let (source, value) = match source {
+                    Ok(source) => source,
+                    Err(e) => {
+                        ctx.eprintln(format_args!("warning: skips {}: {}", name, e));
+                        continue;
                     }
+                };
+                let result = f(&source, &value);
+                if let Some(e) = outcome.add(&result) {
+                    ctx.eprintln(format_args!("{}: {}", name, e));
                 }
-                None => println!("{} is not found", target),
             }
         }
+        outcome.finish()
+    }
+}
+
+/// Returns the name of the input in the results.
+fn name(file: &str) -> &str {
+    if file == "-" {
+        "<stdin>"
+    } else {
+        file
+    }
+}
+
+/// A json input being searched, which tells how its results are prefixed.
+struct Source<'a> {
+    /// The name of the input, if it prefixes the results.
+    name: Option<&'a str>,
+    /// The locations of the values, if they prefix the results.
+    spans: Option<SpanMap>,
+}
+
+impl Source<'_> {
+    /// Returns the prefix of the result at the path, `file:line:column:`
+    /// with --vimgrep.
+    fn prefix(&self, path: &ViewPathBuf) -> String {
+        let mut prefix = String::new();
+        if let Some(name) = self.name {
+            prefix.push_str(name);
+            prefix.push(':');
+        }
+        if let Some(location) = self.spans.as_ref().and_then(|spans| spans.get(path)) {
+            let _ = write!(prefix, "{}:", location);
+        }
+        prefix
+    }
+}
+
+/// The tally of the results of several inputs. Like grep, any error makes
+/// the status an error, otherwise it is not found if no input had a result.
+#[derive(Default)]
+struct Outcome {
+    total: usize,
+    found: usize,
+    failed: usize,
+    worst: Option<Status>,
+}
+
+impl Outcome {
+    /// Counts the result, returning its error unless it is not found.
+    fn add<'a>(&mut self, result: &'a anyhow::Result<()>) -> Option<&'a anyhow::Error> {
+        self.total += 1;
+        match result {
+            Ok(()) => self.found += 1,
+            Err(e) => match Status::of(e) {
+                Status::NotFound => {}
+                status => {
+                    self.failed += 1;
+                    self.worst = self.worst.max(Some(status));
+                    return Some(e);
+                }
+            },
+        }
+        None
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        if let Some(status) = self.worst {
+            let message = format!("{} of {} files failed", self.failed, self.total);
+            anyhow::bail!(Failure { status, message });
+        }
+        if self.found == 0 {
+            let message = format!("nothing is found in {} files", self.total);
+            anyhow::bail!(Failure::not_found(message));
+        }
         Ok(())
     }
 }
 
+/// Sets the value at the path of the json file.
+#[derive(Debug, Args)]
+pub struct Set {
+    /// The path of the value to set.
+    #[clap(long, short)]
+    path: String,
+
+    /// The syntax of the path.
+    #[clap(long, arg_enum, default_value = "path")]
+    syntax: PathSyntax,
+
+    /// The new value, it is parsed as JSON if possible and used as a string
+    /// otherwise.
+    #[clap(long, short)]
+    value: String,
+
+    /// Always uses the new value as a string.
+    #[clap(long, short)]
+    string: bool,
+
+    /// Creates the missing intermediate objects and arrays.
+    #[clap(long, short)]
+    create: bool,
+
+    /// Writes the result back to the json file instead of stdout.
+    #[clap(long, short)]
+    in_place: bool,
+
+    #[clap(name = "json-file")]
+    file: String,
+}
+
+impl crate::TryRun for Set {
+    type Err = anyhow::Error;
+
+    fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
+        let data = ctx.read_to_string(&self.file)?;
+        let mut value = serde_json::from_str::<Value>(&data)?;
+        let new = if self.string {
+            Value::String(self.value.clone())
+        } else {
+            serde_json::from_str::<Value>(&self.value)
+                .unwrap_or_else(|_| Value::String(self.value.clone()))
+        };
+        let path = self.syntax.parse(&self.path, &value)?;
+        ejson::set_path(&mut value, path, new, self.create)?;
+        write_like(ctx, &self.file, &data, &value, self.in_place)
+    }
+}
+
+/// Deletes the value at the path of the json file.
+#[derive(Debug, Args)]
+pub struct Delete {
+    /// The path of the value to delete.
+    #[clap(long, short)]
+    path: String,
+
+    /// The syntax of the path.
+    #[clap(long, arg_enum, default_value = "path")]
+    syntax: PathSyntax,
+
+    /// Writes the result back to the json file instead of stdout.
+    #[clap(long, short)]
+    in_place: bool,
+
+    #[clap(name = "json-file")]
+    file: String,
+}
+
+impl crate::TryRun for Delete {
+    type Err = anyhow::Error;
+
+    fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
+        let data = ctx.read_to_string(&self.file)?;
+        let mut value = serde_json::from_str::<Value>(&data)?;
+        let path = self.syntax.parse(&self.path, &value)?;
+        ejson::remove_path(&mut value, path)?;
+        write_like(ctx, &self.file, &data, &value, self.in_place)
+    }
+}
+
+/// Writes the edited value to the file or stdout, following the layout of
+/// the original text: compact if it was on a single line, otherwise pretty
+/// with the same indentation.
+fn write_like(
+    ctx: &crate::Context,
+    file: &str,
+    original: &str,
+    value: &Value,
+    in_place: bool,
+) -> anyhow::Result<()> {
+    let mut text = match detect_indent(original) {
+        Some(indent) => {
+            let mut buf = Vec::new();
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+            let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+            serde::Serialize::serialize(value, &mut ser)?;
+            String::from_utf8(buf)?
+        }
+        None => serde_json::to_string(value)?,
+    };
+    if original.ends_with('\n') {
+        text.push('\n');
+    }
+    if in_place {
+        ctx.write(file, &text)
+    } else {
+        ctx.println(text.trim_end_matches('\n'));
+        Ok(())
+    }
+}
+
+/// Returns the indentation unit of a pretty json text, which is the
+/// leading whitespace of its first indented line, or `None` if the text
+/// is compact.
+fn detect_indent(text: &str) -> Option<&str> {
+    text.trim()
+        .lines()
+        .skip(1)
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .find(|indent| !indent.is_empty())
+        .or_else(|| text.trim().contains('\n').then_some("  "))
+}
+
 /// Validates, formats or searchs the json file.
 #[derive(Debug, Parser)]
 pub struct Json {
@@ -86,7 +470,7 @@ pub struct Json {
     sub_commands: SubCommands,
 }
 
-crate::define_sub_commands! {SubCommands, Search, Resolve}
+crate::define_sub_commands! {SubCommands, Search, Resolve, Set, Delete}
 
 impl crate::TryRun for Json {
     type Err = anyhow::Error;
@@ -95,3 +479,52 @@ impl crate::TryRun for Json {
         self.sub_commands.run(ctx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Outcome;
+    use crate::{Failure, Status};
+
+    fn not_found() -> anyhow::Result<()> {
+        Err(Failure::not_found("nothing").into())
+    }
+
+    fn io() -> anyhow::Result<()> {
+        Err(std::io::Error::from(std::io::ErrorKind::NotFound).into())
+    }
+
+    fn usage() -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("bad path"))
+    }
+
+    fn finish(results: &[anyhow::Result<()>]) -> Option<Status> {
+        let mut outcome = Outcome::default();
+        for result in results {
+            outcome.add(result);
+        }
+        outcome.finish().err().map(|e| Status::of(&e))
+    }
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(None, finish(&[Ok(()), not_found()]));
+        assert_eq!(Some(Status::NotFound), finish(&[not_found(), not_found()]));
+        assert_eq!(Some(Status::Usage), finish(&[Ok(()), usage()]));
+        assert_eq!(Some(Status::Io), finish(&[usage(), Ok(()), io()]));
+        assert_eq!(Some(Status::Io), finish(&[io(), not_found()]));
+        let walk = Err(Failure::io("cannot walk").into());
+        assert_eq!(Some(Status::Io), finish(&[walk]));
+    }
+
+    #[test]
+    fn test_outcome_add() {
+        let mut outcome = Outcome::default();
+        assert!(outcome.add(&Ok(())).is_none());
+        assert!(outcome.add(&not_found()).is_none());
+        assert!(outcome.add(&io()).is_some());
+        assert_eq!(
+            "1 of 3 files failed",
+            outcome.finish().unwrap_err().to_string()
+        );
+    }
+}
@@ -1 +1,4 @@
 pub mod json;
+mod search;
+pub mod toml;
+pub mod yaml;
@@ -0,0 +1,140 @@
+//! The search and resolve shared by the json, toml and yaml commands, over
+//! any `Navigable` document.
+
+use std::fmt::Display;
+
+use clap::ArgEnum;
+use regex::Regex;
+use view::{Navigable, ViewPath, ViewPathBuf};
+
+use crate::Failure;
+
+/// The form the paths of the results are printed in.
+#[derive(Debug, Clone, Copy, ArgEnum)]
+pub enum PathFormat {
+    /// The path syntax of ebar, like `a.b[0]`.
+    Path,
+    /// JSON Pointer, like `/a/b/0`.
+    Pointer,
+    /// Normalized JSONPath, like `$['a']['b'][0]`.
+    Jsonpath,
+}
+
+impl PathFormat {
+    pub(crate) fn format(&self, path: &ViewPathBuf) -> anyhow::Result<String> {
+        match self {
+            PathFormat::Path => Ok(path.to_string()),
+            PathFormat::Pointer => Ok(path.to_json_pointer()?),
+            PathFormat::Jsonpath => Ok(path.to_json_path()?),
+        }
+    }
+}
+
+/// The syntax the searched paths are written in.
+#[derive(Debug, Clone, Copy, ArgEnum)]
+pub enum PathSyntax {
+    /// The path syntax of ebar, like `a.b[0]`.
+    Path,
+    /// JSONPath, like `$.a.b[0]`.
+    Jsonpath,
+    /// JSON Pointer, like `/a/b/0`.
+    Pointer,
+}
+
+impl PathSyntax {
+    /// Parses the path of the document. The digit tokens of a JSON Pointer
+    /// address the fields of the same name in the objects of the document.
+    pub(crate) fn parse<'a, N: Navigable>(
+        &self,
+        path: &'a str,
+        value: &N,
+    ) -> Result<ViewPath<'a>, view::Error> {
+        match self {
+            PathSyntax::Path => ViewPath::parse_str(path),
+            PathSyntax::Jsonpath => ViewPath::parse_json_path(path),
+            PathSyntax::Pointer => {
+                let pointer = ViewPathBuf::from_json_pointer(path)?;
+                Ok(ViewPath::from(&ejson::resolve_pointer(value, &pointer)).into_owned())
+            }
+        }
+    }
+
+    pub(crate) fn format(&self, path: &ViewPathBuf) -> anyhow::Result<String> {
+        match self {
+            PathSyntax::Path => PathFormat::Path.format(path),
+            PathSyntax::Jsonpath => PathFormat::Jsonpath.format(path),
+            PathSyntax::Pointer => PathFormat::Pointer.format(path),
+        }
+    }
+}
+
+/// Prints the results found in a document.
+pub(crate) struct Output<'a, N> {
+    pub ctx: &'a crate::Context,
+    /// Renders a matched value.
+    pub render: &'a dyn Fn(&N) -> anyhow::Result<String>,
+    /// Prefixes the result at the concrete path, with the file of the
+    /// document for instance.
+    pub prefix: &'a dyn Fn(&ViewPathBuf) -> String,
+}
+
+impl<N: Navigable> Output<'_, N> {
+    /// Prints the result at the concrete path after its prefix, which is
+    /// repeated on each line of a pretty value.
+    pub fn print(&self, path: &ViewPathBuf, result: impl Display) {
+        let prefix = (self.prefix)(path);
+        for line in result.to_string().lines() {
+            self.ctx.println(format_args!("{}{}", prefix, line));
+        }
+    }
+
+    /// Prints the single value addressed by the path, or each value it
+    /// matches after its concrete path. With `full_paths`, the value of a
+    /// singular path is printed after its path too.
+    pub fn search(
+        &self,
+        value: &N,
+        input: &str,
+        path: ViewPath<'_>,
+        syntax: PathSyntax,
+        full_paths: bool,
+    ) -> anyhow::Result<()> {
+        if path.is_singular() && !full_paths {
+            let v = ejson::search_path(value, path)?;
+            self.print(&ViewPathBuf::root(), (self.render)(v)?);
+            return Ok(());
+        }
+        let matches = ejson::search_all(value, path);
+        if matches.is_empty() {
+            let message = format!("`{}` matches nothing", input);
+            anyhow::bail!(Failure::not_found(message));
+        }
+        for (path, v) in matches {
+            let result = format!("{}: {}", syntax.format(&path)?, (self.render)(v)?);
+            self.print(&path, result);
+        }
+        Ok(())
+    }
+
+    /// Prints the leaves whose text is the target after their paths.
+    pub fn resolve_value(&self, value: &N, target: &str, format: PathFormat) -> anyhow::Result<()> {
+        let paths = ejson::nest_find_by(value, |v| v.leaf_to_string().is_some_and(|s| s == target))
+            .ok_or_else(|| Failure::not_found(format!("{} is not found", target)))?;
+        for (path, v) in paths {
+            let result = format!("{}: {}", format.format(&path)?, (self.render)(v)?);
+            self.print(&path, result);
+        }
+        Ok(())
+    }
+
+    /// Prints the leaves whose text matches the regex after their paths.
+    pub fn resolve_regex(&self, value: &N, reg: &Regex, format: PathFormat) -> anyhow::Result<()> {
+        let paths = ejson::nest_find_regex(value, reg.clone())
+            .ok_or_else(|| Failure::not_found(format!("{} is not found", reg)))?;
+        for (path, v) in paths {
+            let result = format!("{}: {}", format.format(&path)?, (self.render)(v)?);
+            self.print(&path, result);
+        }
+        Ok(())
+    }
+}
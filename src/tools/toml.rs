@@ -0,0 +1,154 @@
+use clap::{ArgGroup, Args, Parser};
+use toml::Value;
+
+use super::search::{Output, PathFormat, PathSyntax};
+
+/// Searchs with the specified path from the toml file if the path
+/// is specified. Otherwise, show the pretty toml string.
+///
+/// Paths work as for json files, inline tables are tables and arrays of
+/// tables are arrays, so `package.name` or `bin[0].path` look up a
+/// `Cargo.toml`.
+#[derive(Debug, Args)]
+pub struct Search {
+    /// if --path or -p is specified, searchs from the toml file.
+    #[clap(long, short)]
+    path: Option<String>,
+
+    /// The syntax of the path, the matched paths are printed in it too.
+    #[clap(long, arg_enum, default_value = "path")]
+    syntax: PathSyntax,
+
+    #[clap(name = "toml-file")]
+    file: String,
+}
+
+impl crate::TryRun for Search {
+    type Err = anyhow::Error;
+
+    fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
+        let data = ctx.read_to_string(&self.file)?;
+        let value = toml::from_str::<Value>(&data)?;
+        match &self.path {
+            Some(input) => {
+                let path = self.syntax.parse(input, &value)?;
+                output(ctx).search(&value, input, path, self.syntax, false)?
+            }
+            None => ctx.println(to_toml_string(&value)?),
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the path for the target value from the toml file.
+///
+/// Datetimes are matched by their text, like `1979-05-27T07:32:00Z`.
+#[derive(Debug, Args)]
+#[clap(group(ArgGroup::new("value").required(true).args(&["target", "regex"])))]
+pub struct Resolve {
+    /// The target value to resolve.
+    #[clap(long, short)]
+    target: Option<String>,
+
+    /// The regex target value to resolve.
+    #[clap(long, short)]
+    regex: Option<String>,
+
+    /// The form of the printed paths.
+    #[clap(long, short, arg_enum, default_value = "path")]
+    format: PathFormat,
+
+    #[clap(name = "toml-file")]
+    file: String,
+}
+
+impl crate::TryRun for Resolve {
+    type Err = anyhow::Error;
+
+    fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
+        let data = ctx.read_to_string(&self.file)?;
+        let value = toml::from_str::<Value>(&data)?;
+        if let Some(target) = &self.target {
+            output(ctx).resolve_value(&value, target, self.format)?;
+        } else if let Some(target) = &self.regex {
+            let reg = regex::Regex::new(target)?;
+            output(ctx).resolve_regex(&value, &reg, self.format)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the toml results, which are not prefixed.
+fn output(ctx: &crate::Context) -> Output<'_, Value> {
+    Output {
+        ctx,
+        render: &to_toml_string,
+        prefix: &|_| String::new(),
+    }
+}
+
+/// Renders a table as a toml document and any other value inline, like
+/// the right side of a key.
+fn to_toml_string(value: &Value) -> anyhow::Result<String> {
+    match value {
+        Value::Table(_) => Ok(toml::to_string(value)?.trim_end().to_string()),
+        value => {
+            let mut text = String::new();
+            write_inline(&mut text, value);
+            Ok(text)
+        }
+    }
+}
+
+fn write_inline(out: &mut String, value: &Value) {
+    match value {
+        Value::Array(a) => {
+            out.push('[');
+            for (i, v) in a.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_inline(out, v);
+            }
+            out.push(']');
+        }
+        Value::Table(t) if t.is_empty() => out.push_str("{}"),
+        Value::Table(t) => {
+            out.push_str("{ ");
+            for (i, (k, v)) in t.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                if !k.is_empty()
+                    && k.chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                {
+                    out.push_str(k);
+                } else {
+                    out.push_str(&Value::String(k.clone()).to_string());
+                }
+                out.push_str(" = ");
+                write_inline(out, v);
+            }
+            out.push_str(" }");
+        }
+        v => out.push_str(&v.to_string()),
+    }
+}
+
+/// Searchs the toml file.
+#[derive(Debug, Parser)]
+pub struct Toml {
+    #[clap(subcommand)]
+    sub_commands: SubCommands,
+}
+
+crate::define_sub_commands! {SubCommands, Search, Resolve}
+
+impl crate::TryRun for Toml {
+    type Err = anyhow::Error;
+
+    fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
+        self.sub_commands.run(ctx)
+    }
+}
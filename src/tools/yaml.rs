@@ -0,0 +1,199 @@
+use clap::{ArgGroup, Args, Parser};
+use serde::Deserialize;
+use serde_yaml::Value;
+use view::{Segment, ViewPath};
+
+use super::search::{Output, PathFormat, PathSyntax};
+
+/// Searchs with the specified path from the yaml file if the path
+/// is specified. Otherwise, show the pretty yaml string.
+///
+/// The file may be a stream of `---` separated documents, like Kubernetes
+/// manifests. A path starting with an index or a slice selects the
+/// documents, as in `[1].metadata.name` or `[0:2].kind`, any other path is
+/// searched in every document. The matched paths are always prefixed by the
+/// document number.
+///
+/// In a file of a single document which is a sequence, a leading index or
+/// slice selects the elements of the sequence instead, as in `[0].name`,
+/// which matches `[0][0].name`.
+#[derive(Debug, Args)]
+pub struct Search {
+    /// if --path or -p is specified, searchs from the yaml file. A leading
+    /// index or slice selects the documents, or the elements of a single
+    /// document which is a sequence.
+    #[clap(long, short)]
+    path: Option<String>,
+
+    /// The syntax of the path, the matched paths are printed in it too.
+    #[clap(long, arg_enum, default_value = "path")]
+    syntax: PathSyntax,
+
+    #[clap(name = "yaml-file")]
+    file: String,
+}
+
+impl crate::TryRun for Search {
+    type Err = anyhow::Error;
+
+    fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
+        let data = ctx.read_to_string(&self.file)?;
+        let stream = read_stream(&data)?;
+        match &self.path {
+            Some(input) => {
+                let path = in_stream(self.syntax.parse(input, &stream)?, &stream);
+                output(ctx).search(&stream, input, path, self.syntax, false)?
+            }
+            None => {
+                let docs = stream.as_sequence().into_iter().flatten();
+                for (i, doc) in docs.enumerate() {
+                    if i > 0 {
+                        ctx.println("---");
+                    }
+                    ctx.println(to_yaml_string(doc)?);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the path for the target value from the yaml file, each path
+/// starts with the number of its document.
+#[derive(Debug, Args)]
+#[clap(group(ArgGroup::new("value").required(true).args(&["target", "regex"])))]
+pub struct Resolve {
+    /// The target value to resolve.
+    #[clap(long, short)]
+    target: Option<String>,
+
+    /// The regex target value to resolve.
+    #[clap(long, short)]
+    regex: Option<String>,
+
+    /// The form of the printed paths.
+    #[clap(long, short, arg_enum, default_value = "path")]
+    format: PathFormat,
+
+    #[clap(name = "yaml-file")]
+    file: String,
+}
+
+impl crate::TryRun for Resolve {
+    type Err = anyhow::Error;
+
+    fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
+        let data = ctx.read_to_string(&self.file)?;
+        let stream = read_stream(&data)?;
+        if let Some(target) = &self.target {
+            output(ctx).resolve_value(&stream, target, self.format)?;
+        } else if let Some(target) = &self.regex {
+            let reg = regex::Regex::new(target)?;
+            output(ctx).resolve_regex(&stream, &reg, self.format)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the yaml results, which are not prefixed.
+fn output(ctx: &crate::Context) -> Output<'_, Value> {
+    Output {
+        ctx,
+        render: &to_yaml_string,
+        prefix: &|_| String::new(),
+    }
+}
+
+/// Reads the documents of the stream into a sequence, so the document
+/// number is the first segment of the paths.
+fn read_stream(data: &str) -> anyhow::Result<Value> {
+    let mut docs = Vec::new();
+    for doc in serde_yaml::Deserializer::from_str(data) {
+        docs.push(Value::deserialize(doc)?);
+    }
+    Ok(Value::Sequence(docs))
+}
+
+/// Anchors the path in the stream. A leading index or slice selects the
+/// documents, unless the stream is a single sequence document whose
+/// elements it selects, any other path applies to every document.
+fn in_stream<'a>(mut path: ViewPath<'a>, stream: &Value) -> ViewPath<'a> {
+    let selects = matches!(path.get(0), Some(Segment::Index(_) | Segment::Slice(_)));
+    match stream.as_sequence().map(Vec::as_slice) {
+        Some([Value::Sequence(_)]) if selects => path.push_front(0),
+        _ if selects => {}
+        _ => path.push_front(Segment::wildcard()),
+    }
+    path
+}
+
+fn to_yaml_string(value: &Value) -> anyhow::Result<String> {
+    let text = serde_yaml::to_string(value)?;
+    Ok(text.trim_start_matches("---\n").trim_end().to_string())
+}
+
+/// Searchs the yaml file.
+#[derive(Debug, Parser)]
+pub struct Yaml {
+    #[clap(subcommand)]
+    sub_commands: SubCommands,
+}
+
+crate::define_sub_commands! {SubCommands, Search, Resolve}
+
+impl crate::TryRun for Yaml {
+    type Err = anyhow::Error;
+
+    fn run(&self, ctx: &crate::Context) -> Result<(), Self::Err> {
+        self.sub_commands.run(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::Value;
+    use view::ViewPath;
+
+    use super::{in_stream, read_stream};
+
+    fn search<'a>(stream: &'a Value, path: &str) -> Option<&'a Value> {
+        let path = in_stream(ViewPath::parse_str(path).unwrap(), stream);
+        ejson::search_path(stream, path).ok()
+    }
+
+    fn search_all(stream: &Value, path: &str) -> Vec<String> {
+        let path = in_stream(ViewPath::parse_str(path).unwrap(), stream);
+        ejson::search_all(stream, path)
+            .iter()
+            .map(|(p, _)| p.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_single_document() {
+        let stream = read_stream("metadata:\n  name: a\n").unwrap();
+        assert_eq!(
+            Some(&Value::from("a")),
+            search(&stream, "[0].metadata.name")
+        );
+        assert_eq!(
+            vec!["[0].metadata.name"],
+            search_all(&stream, "metadata.name")
+        );
+
+        // The elements of a single sequence are selected by the leading index.
+        let stream = read_stream("- name: a\n- name: b\n").unwrap();
+        assert_eq!(Some(&Value::from("b")), search(&stream, "[1].name"));
+        assert_eq!(vec!["[0][0].name"], search_all(&stream, "[:1].name"));
+    }
+
+    #[test]
+    fn test_several_documents() {
+        let stream = read_stream("- name: a\n---\nname: b\n---\nname: c\n").unwrap();
+        assert_eq!(Some(&Value::from("a")), search(&stream, "[0][0].name"));
+        assert_eq!(Some(&Value::from("b")), search(&stream, "[1].name"));
+        assert_eq!(vec!["[1].name", "[2].name"], search_all(&stream, "name"));
+        assert_eq!(vec!["[1].name"], search_all(&stream, "[0:2].name"));
+        assert_eq!(vec!["[2].name"], search_all(&stream, "[-1:].name"));
+    }
+}
@@ -0,0 +1,185 @@
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs ebar with the input on its standard input.
+fn ebar(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ebar"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Writes the file in the temporary directory of the tests and returns its
+/// path.
+fn fixture(name: &str, contents: &str) -> String {
+    let path = format!("{}/{}", env!("CARGO_TARGET_TMPDIR"), name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_quiet_prints_errors() {
+    let output = ebar(
+        &["-q", "json", "search", "-p", "a.b["],
+        r#"{"a": {"b": [1]}}"#,
+    );
+    assert_eq!(Some(2), output.status.code());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("invalid path"), "{}", stderr);
+}
+
+#[test]
+fn test_quiet_hides_results() {
+    let output = ebar(
+        &["-q", "json", "search", "-p", "a.b[0]"],
+        r#"{"a": {"b": [1]}}"#,
+    );
+    assert_eq!(Some(0), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_several_files_prefix_every_line() {
+    let file = fixture("prefix.json", r#"{"a": {"b": 1}, "c": [2, 3]}"#);
+    let search = |path| {
+        let args = [
+            "--color", "never", "json", "search", "-p", path, &file, &file,
+        ];
+        let output = ebar(&args, "");
+        assert_eq!(Some(0), output.status.code());
+        stdout(&output).to_string()
+    };
+
+    // A singular path is printed after its path, as the other hits are.
+    let expected = format!("{0}:a: {{\n{0}:  \"b\": 1\n{0}:}}\n", file);
+    assert_eq!(expected.repeat(2), search("a"));
+    let expected = format!("{0}:c[0]: 2\n{0}:c[1]: 3\n", file);
+    assert_eq!(expected.repeat(2), search("c[*]"));
+}
+
+#[test]
+fn test_recursive_missing_root_is_io() {
+    let output = ebar(
+        &["json", "search", "-R", "-p", "id", "/nonexistent/ebar"],
+        "",
+    );
+    assert_eq!(Some(3), output.status.code());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("/nonexistent/ebar"), "{}", stderr);
+}
+
+#[test]
+fn test_recursive_resolve_prints_values() {
+    let root = format!("{}/resolve", env!("CARGO_TARGET_TMPDIR"));
+    fs::create_dir_all(format!("{}/nested", root)).unwrap();
+    let a = fixture("resolve/a.json", r#"{"id": "x", "n": 1}"#);
+    let b = fixture("resolve/nested/b.json", r#"{"items": [{"id": "x"}]}"#);
+    fixture("resolve/nested/c.txt", r#"{"id": "x"}"#);
+
+    let output = ebar(&["json", "resolve", "-R", "-t", "x", &root], "");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!(
+        format!("{}:id: \"x\"\n{}:items[0].id: \"x\"\n", a, b),
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_quiet_hides_misses() {
+    let output = ebar(&["-q", "json", "resolve", "-t", "zzz"], r#"{"a": 1}"#);
+    assert_eq!(Some(1), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_vimgrep_prints_a_line_per_hit() {
+    let file = fixture(
+        "vimgrep.json",
+        "{\"a\": {\"b\": [1, 2]},\n \"c\": {\"b\": {\"d\": null}}}",
+    );
+    let output = ebar(
+        &[
+            "--color",
+            "never",
+            "json",
+            "search",
+            "--vimgrep",
+            "-p",
+            "..b",
+            &file,
+        ],
+        "",
+    );
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!(
+        format!("{0}:1:13:a.b: [1,2]\n{0}:2:13:c.b: {{\"d\":null}}\n", file),
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_yaml_top_level_sequence() {
+    let file = fixture("sequence.yaml", "- name: a\n- name: b\n");
+    let output = ebar(&["yaml", "search", "-p", "[1].name", &file], "");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("b\n", stdout(&output));
+
+    let output = ebar(&["yaml", "resolve", "-t", "b", &file], "");
+    assert_eq!("[0][1].name: b\n", stdout(&output));
+
+    let file = fixture("stream.yaml", "- name: a\n---\nname: b\n");
+    let output = ebar(&["yaml", "search", "-p", "[1].name", &file], "");
+    assert_eq!("b\n", stdout(&output));
+}
+
+#[test]
+fn test_search_json_pointer() {
+    let input = r#"{"a": {"1": "x"}, "b": [{"c": 2}]}"#;
+    let output = ebar(
+        &["json", "search", "--syntax", "pointer", "-p", "/a/1"],
+        input,
+    );
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("\"x\"\n", stdout(&output));
+
+    let output = ebar(
+        &[
+            "--color",
+            "never",
+            "json",
+            "search",
+            "--syntax",
+            "pointer",
+            "-p",
+            "/b/0/c",
+            "--vimgrep",
+            "-",
+        ],
+        input,
+    );
+    assert_eq!("<stdin>:1:31:/b/0/c: 2\n", stdout(&output));
+
+    let output = ebar(
+        &["json", "search", "--syntax", "pointer", "-p", "/a/2"],
+        input,
+    );
+    assert_eq!(Some(1), output.status.code());
+}